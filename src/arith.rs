@@ -0,0 +1,20 @@
+use crate::bigint::BigUint;
+
+pub fn triangle(n: u64) -> u64 {
+    // イテレータから取り出したアイテムの和を求める
+    (1..n+1).sum()
+}
+
+pub fn factorial(n: u64) -> u64 {
+    // イテレータから取り出したアイテムの積を求める
+    (1..n+1).product()
+}
+
+// u64に収まらない場合でも正確に求められるもの
+pub fn big_triangle(n: u64) -> BigUint {
+    (1..n+1).map(BigUint::from).sum()
+}
+
+pub fn big_factorial(n: u64) -> BigUint {
+    (1..n+1).map(BigUint::from).product()
+}
@@ -0,0 +1,429 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
+// 10進数への変換で、一度に扱う桁数
+const DECIMAL_DIGITS: usize = 9;
+const DECIMAL_BASE: u32 = 1_000_000_000;
+
+// 任意の大きさの符号なし整数
+// 32ビットずつ下位から並べ、最上位の0は持たない（0は空のVec）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    limbs: Vec<u32>
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        BigUint { limbs: Vec::new() }
+    }
+
+    pub fn one() -> Self {
+        BigUint { limbs: vec![1] }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    // 2進数での桁数
+    pub fn bits(&self) -> u64 {
+        match self.limbs.last() {
+            Some(&top) => self.limbs.len() as u64 * 32 - u64::from(top.leading_zeros()),
+            None => 0
+        }
+    }
+
+    fn normalized(mut self) -> Self {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        self
+    }
+
+    fn mul_small(&mut self, factor: u32, addend: u32) {
+        let mut carry = u64::from(addend);
+        for limb in &mut self.limbs {
+            let value = u64::from(*limb) * u64::from(factor) + carry;
+            *limb = value as u32;
+            carry = value >> 32;
+        }
+        if carry != 0 {
+            self.limbs.push(carry as u32);
+        }
+        *self = std::mem::take(self).normalized();
+    }
+
+    // divisorで割った余りを返し、自身は商にする
+    fn div_rem_small(&mut self, divisor: u32) -> u32 {
+        let mut rem = 0u64;
+        for limb in self.limbs.iter_mut().rev() {
+            let value = (rem << 32) | u64::from(*limb);
+            *limb = (value / u64::from(divisor)) as u32;
+            rem = value % u64::from(divisor);
+        }
+        *self = std::mem::take(self).normalized();
+        rem as u32
+    }
+
+    // self >= rhsの場合だけNone以外を返す
+    pub fn checked_sub(&self, rhs: &BigUint) -> Option<BigUint> {
+        if *self < *rhs {
+            return None;
+        }
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = 0i64;
+        for (i, &limb) in self.limbs.iter().enumerate() {
+            let mut value = i64::from(limb) - i64::from(rhs.limbs.get(i).cloned().unwrap_or(0)) - borrow;
+            borrow = if value < 0 { 1 } else { 0 };
+            if value < 0 {
+                value += 1 << 32;
+            }
+            limbs.push(value as u32);
+        }
+        Some(BigUint { limbs }.normalized())
+    }
+}
+
+macro_rules! big_uint_from {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigUint {
+                fn from(value: $t) -> Self {
+                    let mut value = value as u128;
+                    let mut limbs = Vec::new();
+                    while value != 0 {
+                        limbs.push(value as u32);
+                        value >>= 32;
+                    }
+                    BigUint { limbs }
+                }
+            }
+        )*
+    }
+}
+
+big_uint_from!(u8, u16, u32, u64, u128, usize);
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs.len().cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, rhs: &BigUint) -> BigUint {
+        let (long, short) = if self.limbs.len() >= rhs.limbs.len() { (self, rhs) } else { (rhs, self) };
+        let mut limbs = Vec::with_capacity(long.limbs.len() + 1);
+        let mut carry = 0u64;
+        for (i, &limb) in long.limbs.iter().enumerate() {
+            let value = u64::from(limb) + u64::from(short.limbs.get(i).cloned().unwrap_or(0)) + carry;
+            limbs.push(value as u32);
+            carry = value >> 32;
+        }
+        if carry != 0 {
+            limbs.push(carry as u32);
+        }
+        BigUint { limbs }
+    }
+}
+
+// u64などと同じく、結果が負になる場合はpanicする
+impl Sub<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn sub(self, rhs: &BigUint) -> BigUint {
+        self.checked_sub(rhs).expect("attempt to subtract with overflow")
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, rhs: &BigUint) -> BigUint {
+        if self.is_zero() || rhs.is_zero() {
+            return BigUint::zero();
+        }
+        let mut limbs = vec![0u32; self.limbs.len() + rhs.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in rhs.limbs.iter().enumerate() {
+                let value = u64::from(a) * u64::from(b) + u64::from(limbs[i + j]) + carry;
+                limbs[i + j] = value as u32;
+                carry = value >> 32;
+            }
+            limbs[i + rhs.limbs.len()] = carry as u32;
+        }
+        BigUint { limbs }.normalized()
+    }
+}
+
+// 参照同士の演算を使って、値を取る組み合わせも実装する
+macro_rules! forward_binop {
+    ($t:ty, $($trait:ident $method:ident),*) => {
+        $(
+            impl $trait<$t> for $t {
+                type Output = $t;
+
+                fn $method(self, rhs: $t) -> $t {
+                    (&self).$method(&rhs)
+                }
+            }
+
+            impl<'a> $trait<&'a $t> for $t {
+                type Output = $t;
+
+                fn $method(self, rhs: &$t) -> $t {
+                    (&self).$method(rhs)
+                }
+            }
+
+            impl<'a> $trait<$t> for &'a $t {
+                type Output = $t;
+
+                fn $method(self, rhs: $t) -> $t {
+                    self.$method(&rhs)
+                }
+            }
+        )*
+    }
+}
+
+forward_binop!(BigUint, Add add, Sub sub, Mul mul);
+
+// (1..=n).map(BigUint::from).sum()/product()のように使えるようにする
+macro_rules! sum_product {
+    ($t:ty) => {
+        impl Sum for $t {
+            fn sum<I: Iterator<Item=$t>>(iter: I) -> $t {
+                iter.fold(<$t>::zero(), |sum, x| sum + x)
+            }
+        }
+
+        impl<'a> Sum<&'a $t> for $t {
+            fn sum<I: Iterator<Item=&'a $t>>(iter: I) -> $t {
+                iter.fold(<$t>::zero(), |sum, x| sum + x)
+            }
+        }
+
+        impl Product for $t {
+            fn product<I: Iterator<Item=$t>>(iter: I) -> $t {
+                iter.fold(<$t>::one(), |product, x| product * x)
+            }
+        }
+
+        impl<'a> Product<&'a $t> for $t {
+            fn product<I: Iterator<Item=&'a $t>>(iter: I) -> $t {
+                iter.fold(<$t>::one(), |product, x| product * x)
+            }
+        }
+    }
+}
+
+sum_product!(BigUint);
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // 10^9で割った余りを下の桁から集める
+        let mut rest = self.clone();
+        let mut chunks = Vec::new();
+        while !rest.is_zero() {
+            chunks.push(rest.div_rem_small(DECIMAL_BASE));
+        }
+        let mut digits = chunks.pop().unwrap_or(0).to_string();
+        for chunk in chunks.iter().rev() {
+            digits.push_str(&format!("{:01$}", chunk, DECIMAL_DIGITS));
+        }
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+// 10進数の文字列として読めなかったことを表すエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBigIntError {
+    pub input: String
+}
+
+impl fmt::Display for ParseBigIntError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid integer: {:?}", self.input)
+    }
+}
+
+impl std::error::Error for ParseBigIntError {}
+
+impl FromStr for BigUint {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s.strip_prefix('+').unwrap_or(s);
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseBigIntError { input: s.to_string() });
+        }
+        let mut value = BigUint::zero();
+        for chunk in digits.as_bytes().chunks(DECIMAL_DIGITS) {
+            let chunk = std::str::from_utf8(chunk).unwrap();
+            value.mul_small(10u32.pow(chunk.len() as u32), chunk.parse().unwrap());
+        }
+        Ok(value)
+    }
+}
+
+// 任意の大きさの符号付き整数。0は常に負でないものとして持つ
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: BigUint
+}
+
+impl BigInt {
+    pub fn zero() -> Self {
+        BigInt::default()
+    }
+
+    pub fn one() -> Self {
+        BigInt::from(BigUint::one())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn magnitude(&self) -> &BigUint {
+        &self.magnitude
+    }
+
+    fn new(negative: bool, magnitude: BigUint) -> Self {
+        BigInt { negative: negative && !magnitude.is_zero(), magnitude }
+    }
+}
+
+impl From<BigUint> for BigInt {
+    fn from(magnitude: BigUint) -> Self {
+        BigInt::new(false, magnitude)
+    }
+}
+
+macro_rules! big_int_from {
+    ($($unsigned:ty),* ; $($signed:ty),*) => {
+        $(
+            impl From<$unsigned> for BigInt {
+                fn from(value: $unsigned) -> Self {
+                    BigInt::from(BigUint::from(value))
+                }
+            }
+        )*
+        $(
+            impl From<$signed> for BigInt {
+                fn from(value: $signed) -> Self {
+                    BigInt::new(value < 0, BigUint::from((value as i128).unsigned_abs()))
+                }
+            }
+        )*
+    }
+}
+
+big_int_from!(u8, u16, u32, u64, u128, usize; i8, i16, i32, i64, i128, isize);
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.magnitude.clone())
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.magnitude)
+    }
+}
+
+impl Add<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, rhs: &BigInt) -> BigInt {
+        if self.negative == rhs.negative {
+            return BigInt::new(self.negative, &self.magnitude + &rhs.magnitude);
+        }
+        // 符号が異なる場合は、絶対値の大きい方から小さい方を引く
+        match self.magnitude.cmp(&rhs.magnitude) {
+            Ordering::Less => BigInt::new(rhs.negative, &rhs.magnitude - &self.magnitude),
+            _ => BigInt::new(self.negative, &self.magnitude - &rhs.magnitude)
+        }
+    }
+}
+
+impl Sub<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, rhs: &BigInt) -> BigInt {
+        self + &-rhs
+    }
+}
+
+impl Mul<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, rhs: &BigInt) -> BigInt {
+        BigInt::new(self.negative != rhs.negative, &self.magnitude * &rhs.magnitude)
+    }
+}
+
+forward_binop!(BigInt, Add add, Sub sub, Mul mul);
+sum_product!(BigInt);
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(!self.negative, "", &self.magnitude.to_string())
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) if !digits.starts_with('+') => (true, digits),
+            Some(_) => return Err(ParseBigIntError { input: s.to_string() }),
+            None => (false, s)
+        };
+        digits.parse::<BigUint>()
+            .map(|magnitude| BigInt::new(negative, magnitude))
+            .map_err(|_| ParseBigIntError { input: s.to_string() })
+    }
+}
@@ -0,0 +1,338 @@
+use std::collections::BTreeSet;
+use std::fmt::{self, Debug};
+use std::iter::Peekable;
+
+// パーサが失敗した位置と、その位置で期待されていたものの集合
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub position: usize,
+    pub expected: BTreeSet<String>,
+    pub found: Option<String>
+}
+
+impl ParseError {
+    // より先まで進んだ方のエラーを残し、同じ位置ならexpectedを合わせる
+    fn merge(self, other: ParseError) -> ParseError {
+        if self.position > other.position {
+            self
+        } else if self.position < other.position {
+            other
+        } else {
+            let mut merged = self;
+            merged.expected.extend(other.expected);
+            merged
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let expected: Vec<&str> = self.expected.iter().map(String::as_str).collect();
+        write!(f, "at position {}: expected ", self.position)?;
+        if expected.len() > 1 {
+            write!(f, "one of ")?;
+        }
+        write!(f, "{}, found {}", expected.join(", "),
+               self.found.as_ref().map_or("end of input", String::as_str))
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+// パーサへの入力。元のイテレータをクローンしておくことで任意の位置まで巻き戻せる
+pub struct Input<I>
+    where I: Iterator
+{
+    tokens: Peekable<I>,
+    position: usize,
+    furthest: Option<ParseError>
+}
+
+// Input::checkpoint()で保存し、Input::restore()で戻す位置
+pub struct Checkpoint<I>
+    where I: Iterator
+{
+    tokens: Peekable<I>,
+    position: usize
+}
+
+impl<I> Input<I>
+    where I: Iterator + Clone,
+          I::Item: Clone + Debug
+{
+    pub fn new<T>(tokens: T) -> Input<I>
+        where T: IntoIterator<Item=I::Item, IntoIter=I>
+    {
+        Input { tokens: tokens.into_iter().peekable(), position: 0, furthest: None }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn peek(&mut self) -> Option<&I::Item> {
+        self.tokens.peek()
+    }
+
+    // 1アイテム消費して位置を進める
+    pub fn advance(&mut self) -> Option<I::Item> {
+        let item = self.tokens.next();
+        if item.is_some() {
+            self.position += 1;
+        }
+        item
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint<I> {
+        Checkpoint { tokens: self.tokens.clone(), position: self.position }
+    }
+
+    pub fn restore(&mut self, checkpoint: Checkpoint<I>) {
+        self.tokens = checkpoint.tokens;
+        self.position = checkpoint.position;
+    }
+
+    // 現在の位置でexpectedが見つからなかったことを記録する
+    // バックトラックで捨てられた失敗も含め、最も先まで進んだ失敗をエラーとして返す
+    pub fn error<S>(&mut self, expected: S) -> ParseError
+        where S: Into<String>
+    {
+        let mut set = BTreeSet::new();
+        set.insert(expected.into());
+        let found = self.tokens.peek().map(|t| format!("{:?}", t));
+        let error = ParseError { position: self.position, expected: set, found };
+        let merged = match self.furthest.take() {
+            Some(furthest) => furthest.merge(error),
+            None => error
+        };
+        self.furthest = Some(merged.clone());
+        merged
+    }
+}
+
+pub trait Parser<I>
+    where I: Iterator
+{
+    type Output;
+    fn parse(&self, input: &mut Input<I>) -> ParseResult<Self::Output>;
+}
+
+// Inputを受け取ってParseResultを返すクロージャはそのままパーサになる
+impl<I, O, F> Parser<I> for F
+    where I: Iterator,
+          F: Fn(&mut Input<I>) -> ParseResult<O>
+{
+    type Output = O;
+
+    fn parse(&self, input: &mut Input<I>) -> ParseResult<O> {
+        self(input)
+    }
+}
+
+// predicateを満たすアイテムを1つ読む。expectedはエラーメッセージに使われる
+pub fn satisfy<I, P>(expected: &'static str, predicate: P) -> impl Parser<I, Output=I::Item>
+    where I: Iterator + Clone,
+          I::Item: Clone + Debug,
+          P: Fn(&I::Item) -> bool
+{
+    move |input: &mut Input<I>| {
+        match input.peek() {
+            Some(item) if predicate(item) => Ok(input.advance().unwrap()),
+            _ => Err(input.error(expected))
+        }
+    }
+}
+
+// itemと等しいアイテムを1つ読む
+pub fn token<I>(item: I::Item) -> impl Parser<I, Output=I::Item>
+    where I: Iterator + Clone,
+          I::Item: Clone + Debug + PartialEq
+{
+    let expected = format!("{:?}", item);
+    move |input: &mut Input<I>| {
+        match input.peek() {
+            Some(t) if *t == item => Ok(input.advance().unwrap()),
+            _ => Err(input.error(expected.clone()))
+        }
+    }
+}
+
+// 入力の終わりにだけ成功する
+pub fn eof<I>() -> impl Parser<I, Output=()>
+    where I: Iterator + Clone,
+          I::Item: Clone + Debug
+{
+    |input: &mut Input<I>| {
+        match input.peek() {
+            None => Ok(()),
+            Some(_) => Err(input.error("end of input"))
+        }
+    }
+}
+
+pub fn map<I, P, F, O>(parser: P, f: F) -> impl Parser<I, Output=O>
+    where I: Iterator,
+          P: Parser<I>,
+          F: Fn(P::Output) -> O
+{
+    move |input: &mut Input<I>| parser.parse(input).map(&f)
+}
+
+// firstとsecondを順に適用し、両方の結果を組にして返す
+pub fn seq<I, A, B>(first: A, second: B) -> impl Parser<I, Output=(A::Output, B::Output)>
+    where I: Iterator,
+          A: Parser<I>,
+          B: Parser<I>
+{
+    move |input: &mut Input<I>| {
+        let a = first.parse(input)?;
+        let b = second.parse(input)?;
+        Ok((a, b))
+    }
+}
+
+// seq()の結果のうち左側だけを残す
+pub fn left<I, A, B>(first: A, second: B) -> impl Parser<I, Output=A::Output>
+    where I: Iterator,
+          A: Parser<I>,
+          B: Parser<I>
+{
+    map(seq(first, second), |(a, _)| a)
+}
+
+// seq()の結果のうち右側だけを残す
+pub fn right<I, A, B>(first: A, second: B) -> impl Parser<I, Output=B::Output>
+    where I: Iterator,
+          A: Parser<I>,
+          B: Parser<I>
+{
+    map(seq(first, second), |(_, b)| b)
+}
+
+// firstが失敗したら入力を巻き戻してsecondを試す
+pub fn alt<I, A, B>(first: A, second: B) -> impl Parser<I, Output=A::Output>
+    where I: Iterator + Clone,
+          I::Item: Clone + Debug,
+          A: Parser<I>,
+          B: Parser<I, Output=A::Output>
+{
+    move |input: &mut Input<I>| {
+        let checkpoint = input.checkpoint();
+        match first.parse(input) {
+            Ok(a) => Ok(a),
+            Err(_) => {
+                input.restore(checkpoint);
+                second.parse(input)
+            }
+        }
+    }
+}
+
+// 失敗したら入力を巻き戻してNoneを返す
+pub fn optional<I, P>(parser: P) -> impl Parser<I, Output=Option<P::Output>>
+    where I: Iterator + Clone,
+          I::Item: Clone + Debug,
+          P: Parser<I>
+{
+    move |input: &mut Input<I>| {
+        let checkpoint = input.checkpoint();
+        match parser.parse(input) {
+            Ok(a) => Ok(Some(a)),
+            Err(_) => {
+                input.restore(checkpoint);
+                Ok(None)
+            }
+        }
+    }
+}
+
+// parserが失敗するまで繰り返し適用し、結果をitemsに追加する
+// 入力を消費せずに成功するパーサでも無限ループしないよう、その時点で打ち切る
+fn repeat_into<I, P>(parser: &P, input: &mut Input<I>, items: &mut Vec<P::Output>)
+    where I: Iterator + Clone,
+          I::Item: Clone + Debug,
+          P: Parser<I>
+{
+    loop {
+        let checkpoint = input.checkpoint();
+        match parser.parse(input) {
+            Ok(item) => {
+                let progressed = input.position() > checkpoint.position;
+                items.push(item);
+                if !progressed {
+                    return;
+                }
+            }
+            Err(_) => {
+                input.restore(checkpoint);
+                return;
+            }
+        }
+    }
+}
+
+// 0回以上の繰り返し
+pub fn many<I, P>(parser: P) -> impl Parser<I, Output=Vec<P::Output>>
+    where I: Iterator + Clone,
+          I::Item: Clone + Debug,
+          P: Parser<I>
+{
+    move |input: &mut Input<I>| {
+        let mut items = Vec::new();
+        repeat_into(&parser, input, &mut items);
+        Ok(items)
+    }
+}
+
+// 1回以上の繰り返し
+pub fn many1<I, P>(parser: P) -> impl Parser<I, Output=Vec<P::Output>>
+    where I: Iterator + Clone,
+          I::Item: Clone + Debug,
+          P: Parser<I>
+{
+    move |input: &mut Input<I>| {
+        let mut items = vec![parser.parse(input)?];
+        repeat_into(&parser, input, &mut items);
+        Ok(items)
+    }
+}
+
+// separatorで区切られた0個以上のparserの並び
+pub fn sep_by<I, P, S>(parser: P, separator: S) -> impl Parser<I, Output=Vec<P::Output>>
+    where I: Iterator + Clone,
+          I::Item: Clone + Debug,
+          P: Parser<I>,
+          S: Parser<I>
+{
+    move |input: &mut Input<I>| {
+        let mut items = Vec::new();
+        let checkpoint = input.checkpoint();
+        match parser.parse(input) {
+            Ok(item) => items.push(item),
+            Err(_) => {
+                input.restore(checkpoint);
+                return Ok(items);
+            }
+        }
+        // repeat_into()と同じく、区切りと要素のどちらも入力を消費しなければ打ち切る
+        loop {
+            let checkpoint = input.checkpoint();
+            let item = separator.parse(input).and_then(|_| parser.parse(input));
+            match item {
+                Ok(item) => {
+                    let progressed = input.position() > checkpoint.position;
+                    items.push(item);
+                    if !progressed {
+                        return Ok(items);
+                    }
+                }
+                Err(_) => {
+                    input.restore(checkpoint);
+                    return Ok(items);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use std::collections::VecDeque;
+use std::fmt::{self, Debug};
+
+// 検査する性質
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Law {
+    // MAX_ITEMS個以内で終わること
+    Finite,
+    // 一度Noneを返したら、その後もNoneを返し続けること
+    Fused,
+    // size_hint()の下限と上限の間に、実際に残っているアイテム数が収まること
+    SizeHint,
+    // ExactSizeIterator::len()が実際に残っているアイテム数と一致すること
+    ExactSizeLen,
+    // next()とnext_back()をどう混ぜて呼んでも、前後から同じ列を取り出すこと
+    DoubleEnded,
+    // nth(n)がnext()をn+1回呼んだ結果と一致すること
+    Nth,
+    // fold()がnext()で順に取り出した結果と一致すること
+    Fold
+}
+
+// 性質が破られたことと、それを再現する最小の呼び出し手順
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub law: Law,
+    pub counterexample: String
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} law violated: {}", self.law, self.counterexample)
+    }
+}
+
+impl std::error::Error for Violation {}
+
+pub const MAX_ITEMS: usize = 10_000;
+
+// next_back()を混ぜた呼び出し手順を総当たりする長さの上限
+pub const MAX_PATTERN_LEN: usize = 10;
+
+// Noneを返した後にさらにnext()を呼ぶ回数
+const CALLS_AFTER_NONE: usize = 3;
+
+fn violation(law: Law, counterexample: String) -> Result<(), Violation> {
+    Err(Violation { law, counterexample })
+}
+
+// next()だけで全てのアイテムを取り出し、期待値とする
+fn reference<I>(iter: I) -> Result<Vec<I::Item>, Violation>
+    where I: Iterator
+{
+    let mut items = Vec::new();
+    for item in iter {
+        items.push(item);
+        if items.len() > MAX_ITEMS {
+            return Err(Violation {
+                law: Law::Finite,
+                counterexample: format!("produced more than {} items", MAX_ITEMS)
+            });
+        }
+    }
+    Ok(items)
+}
+
+// factoryが作るイテレータが、Iteratorトレイトの約束を守っているかを検査する
+// factoryは呼ぶたびに同じ列を生成する新しいイテレータを返さなければならない
+pub fn check_iterator<F, I>(factory: F) -> Result<(), Violation>
+    where F: Fn() -> I,
+          I: Iterator,
+          I::Item: PartialEq + Debug
+{
+    let expected = reference(factory())?;
+    let len = expected.len();
+
+    // size_hint()
+    let mut iter = factory();
+    for calls in 0..=len {
+        let (lower, upper) = iter.size_hint();
+        let remaining = len - calls;
+        if lower > remaining || upper.is_some_and(|upper| upper < remaining) {
+            return violation(Law::SizeHint, format!(
+                "after {} call(s) to next(), size_hint() returned ({}, {:?}) but {} item(s) remained",
+                calls, lower, upper, remaining));
+        }
+        iter.next();
+    }
+
+    // Noneの後
+    let mut iter = factory();
+    iter.by_ref().take(len).for_each(drop);
+    for extra in 0..=CALLS_AFTER_NONE {
+        if let Some(item) = iter.next() {
+            return violation(Law::Fused, format!(
+                "next() returned None after {} item(s), then {:?} on call {}",
+                len, item, len + extra + 1));
+        }
+    }
+
+    // nth()
+    for n in 0..=len {
+        let mut iter = factory();
+        let got = iter.nth(n);
+        if got.as_ref() != expected.get(n) {
+            return violation(Law::Nth, format!(
+                "nth({}) returned {:?} but next() yields {:?} at that position",
+                n, got, expected.get(n)));
+        }
+        let after = iter.next();
+        if n < len && after.as_ref() != expected.get(n + 1) {
+            return violation(Law::Nth, format!(
+                "next() after nth({}) returned {:?} but expected {:?}",
+                n, after, expected.get(n + 1)));
+        }
+    }
+
+    // fold()
+    let folded = factory().fold(Vec::new(), |mut v, item| { v.push(item); v });
+    if folded != expected {
+        let i = folded.iter().zip(&expected).take_while(|&(a, b)| a == b).count();
+        return violation(Law::Fold, format!(
+            "fold() visited {:?} at index {} but next() yields {:?}",
+            folded.get(i), i, expected.get(i)));
+    }
+
+    Ok(())
+}
+
+// check_iterator()に加えて、len()が正確かを検査する
+pub fn check_exact_size<F, I>(factory: F) -> Result<(), Violation>
+    where F: Fn() -> I,
+          I: ExactSizeIterator,
+          I::Item: PartialEq + Debug
+{
+    check_iterator(&factory)?;
+    let len = factory().count();
+    let mut iter = factory();
+    for calls in 0..=len {
+        if iter.len() != len - calls {
+            return violation(Law::ExactSizeLen, format!(
+                "after {} call(s) to next(), len() returned {} but {} item(s) remained",
+                calls, iter.len(), len - calls));
+        }
+        iter.next();
+    }
+    Ok(())
+}
+
+// 同じ呼び出しが続く部分は「next() x 19」のようにまとめて表示する
+fn describe(pattern: &[bool]) -> String {
+    let mut calls = Vec::new();
+    let mut rest = pattern;
+    while let Some(&back) = rest.first() {
+        let run = rest.iter().take_while(|&&b| b == back).count();
+        let call = if back { "next_back()" } else { "next()" };
+        if run > 2 {
+            calls.push(format!("{} x {}", call, run));
+        } else {
+            for _ in 0..run {
+                calls.push(call.to_string());
+            }
+        }
+        rest = &rest[run..];
+    }
+    calls.join(", ")
+}
+
+// patternの順にnext()とnext_back()を呼び、両端から取り出した結果をexpectedと比べる
+fn run_pattern<F, I>(factory: &F, expected: &[I::Item], pattern: &[bool]) -> Result<(), Violation>
+    where F: Fn() -> I,
+          I: DoubleEndedIterator,
+          I::Item: PartialEq + Debug
+{
+    let mut model: VecDeque<&I::Item> = expected.iter().collect();
+    let mut iter = factory();
+    for (step, &back) in pattern.iter().enumerate() {
+        let (got, want) = if back {
+            (iter.next_back(), model.pop_back())
+        } else {
+            (iter.next(), model.pop_front())
+        };
+        if got.as_ref() != want {
+            return violation(Law::DoubleEnded, format!(
+                "calling {} returned {:?} on the last call but expected {:?}",
+                describe(&pattern[..=step]), got, want));
+        }
+    }
+    Ok(())
+}
+
+// check_iterator()に加えて、next()とnext_back()を混ぜて呼んだ時の結果を検査する
+// 短い手順から順に試すので、最初に見つかった手順が最小の反例になる
+pub fn check_double_ended<F, I>(factory: F) -> Result<(), Violation>
+    where F: Fn() -> I,
+          I: DoubleEndedIterator,
+          I::Item: PartialEq + Debug
+{
+    check_iterator(&factory)?;
+    let expected = reference(factory())?;
+
+    let reversed = factory().rev().collect::<Vec<_>>();
+    if reversed.iter().ne(expected.iter().rev()) {
+        return violation(Law::DoubleEnded, format!(
+            "rev() yielded {:?} but next() yields {:?} in reverse", reversed, expected));
+    }
+
+    // 最後の1回は、両端が出会った後にNoneを返すかどうかの確認になる
+    let total = expected.len() + 1;
+    let max_len = std::cmp::min(total, MAX_PATTERN_LEN);
+    for len in 1..=max_len {
+        for bits in 0..(1u32 << len) {
+            let pattern: Vec<bool> = (0..len).map(|i| bits & (1 << i) != 0).collect();
+            run_pattern(&factory, &expected, &pattern)?;
+        }
+    }
+
+    // 長い列では、両端が出会う直前まで進めてから残りの呼び出し方を総当たりする
+    // 前からと後ろからの進め方は、前だけ・半々・後ろだけの3通りを試す
+    if total > MAX_PATTERN_LEN {
+        let advance = total - MAX_PATTERN_LEN;
+        let mut fronts = vec![advance, advance / 2, 0];
+        fronts.dedup();
+        for front in fronts {
+            let prefix: Vec<bool> = (0..advance).map(|i| i >= front).collect();
+            for bits in 0..(1u32 << MAX_PATTERN_LEN) {
+                let mut pattern = prefix.clone();
+                pattern.extend((0..MAX_PATTERN_LEN).map(|i| bits & (1 << i) != 0));
+                run_pattern(&factory, &expected, &pattern)?;
+            }
+        }
+    }
+    Ok(())
+}
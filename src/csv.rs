@@ -0,0 +1,261 @@
+use std::fmt;
+use std::io::{self, BufRead};
+use std::rc::Rc;
+use std::str::FromStr;
+
+#[derive(Debug)]
+pub enum CsvErrorKind {
+    Io(io::Error),
+    // 引用符で囲まれたフィールドが閉じないまま入力が終わった
+    UnterminatedQuote,
+    // 引用符で囲まれていないフィールドの途中に'"'がある
+    UnexpectedQuote,
+    // 閉じの'"'の直後に区切り文字でも改行でもない文字がある
+    TrailingCharacter(char),
+    MissingColumn(String),
+    Parse { value: String, message: String }
+}
+
+// rowはレコードが始まる行番号、columnはフィールドの番号（どちらも1始まり）
+#[derive(Debug)]
+pub struct CsvError {
+    pub row: usize,
+    pub column: usize,
+    pub kind: CsvErrorKind
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "row {}, column {}: ", self.row, self.column)?;
+        match self.kind {
+            CsvErrorKind::Io(ref e) => write!(f, "I/O error: {}", e),
+            CsvErrorKind::UnterminatedQuote => write!(f, "unterminated quoted field"),
+            CsvErrorKind::UnexpectedQuote => write!(f, "unexpected quote in unquoted field"),
+            CsvErrorKind::TrailingCharacter(c) => write!(f, "unexpected {:?} after closing quote", c),
+            CsvErrorKind::MissingColumn(ref name) => write!(f, "no such column {:?}", name),
+            CsvErrorKind::Parse { ref value, ref message } => write!(f, "cannot parse {:?}: {}", value, message)
+        }
+    }
+}
+
+impl std::error::Error for CsvError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self.kind {
+            CsvErrorKind::Io(ref e) => Some(e),
+            _ => None
+        }
+    }
+}
+
+// 1レコード分のフィールド
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    row: usize,
+    fields: Vec<String>,
+    headers: Option<Rc<Vec<String>>>
+}
+
+impl Record {
+    // このレコードが始まる行番号（1始まり）
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.fields.get(index).map(String::as_str)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=&str> {
+        self.fields.iter().map(String::as_str)
+    }
+
+    fn error(&self, index: usize, kind: CsvErrorKind) -> CsvError {
+        CsvError { row: self.row, column: index + 1, kind }
+    }
+
+    // index番目（0始まり）のフィールドをFromStrで型変換する
+    pub fn parse<T>(&self, index: usize) -> Result<T, CsvError>
+        where T: FromStr,
+              T::Err: fmt::Display
+    {
+        let value = self.get(index)
+            .ok_or_else(|| self.error(index, CsvErrorKind::MissingColumn(index.to_string())))?;
+        value.parse().map_err(|e: T::Err| {
+            self.error(index, CsvErrorKind::Parse { value: value.to_string(), message: e.to_string() })
+        })
+    }
+
+    // ヘッダ行の名前でフィールドを指定してparse()する
+    pub fn parse_by_name<T>(&self, name: &str) -> Result<T, CsvError>
+        where T: FromStr,
+              T::Err: fmt::Display
+    {
+        let index = self.headers.as_ref()
+            .and_then(|headers| headers.iter().position(|h| h == name));
+        match index {
+            Some(index) => self.parse(index),
+            None => Err(CsvError { row: self.row, column: 0, kind: CsvErrorKind::MissingColumn(name.to_string()) })
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    StartField,
+    Unquoted,
+    Quoted,
+    QuoteInQuoted
+}
+
+// BufReadからRFC 4180形式のレコードを1つずつ読むイテレータ
+// 引用符で囲まれたフィールドの中の区切り文字や改行はフィールドの一部として扱う
+pub struct Reader<R>
+    where R: BufRead
+{
+    reader: R,
+    delimiter: char,
+    has_headers: bool,
+    headers: Option<Rc<Vec<String>>>,
+    line: usize,
+    done: bool
+}
+
+impl<R> Reader<R>
+    where R: BufRead
+{
+    pub fn new(reader: R) -> Reader<R> {
+        Reader { reader, delimiter: ',', has_headers: false, headers: None, line: 0, done: false }
+    }
+
+    // 区切り文字を変更する（TSVなら'\t'）
+    pub fn delimiter(mut self, delimiter: char) -> Reader<R> {
+        self.delimiter = delimiter;
+        self
+    }
+
+    // 最初のレコードをヘッダ行として扱う
+    pub fn has_headers(mut self, has_headers: bool) -> Reader<R> {
+        self.has_headers = has_headers;
+        self
+    }
+
+    // ヘッダ行を返す。まだ読んでいなければここで読む
+    pub fn headers(&mut self) -> Result<Option<&[String]>, CsvError> {
+        if self.has_headers && self.headers.is_none() && !self.done {
+            if let Some((_, fields)) = self.read_record()? {
+                self.headers = Some(Rc::new(fields));
+            }
+        }
+        Ok(self.headers.as_ref().map(|h| h.as_slice()))
+    }
+
+    fn read_line(&mut self, buf: &mut String) -> Result<usize, CsvError> {
+        buf.clear();
+        let n = self.reader.read_line(buf)
+            .map_err(|e| CsvError { row: self.line + 1, column: 0, kind: CsvErrorKind::Io(e) })?;
+        if n > 0 {
+            self.line += 1;
+        }
+        Ok(n)
+    }
+
+    // 次のレコードを読んで(開始行, フィールド)を返す。空行は読み飛ばす
+    fn read_record(&mut self) -> Result<Option<(usize, Vec<String>)>, CsvError> {
+        let mut line = String::new();
+        loop {
+            if self.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            if !line.trim_end_matches(&['\r', '\n'][..]).is_empty() {
+                break;
+            }
+        }
+
+        let row = self.line;
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut state = State::StartField;
+        loop {
+            let mut chars = line.chars().peekable();
+            while let Some(c) = chars.next() {
+                let column = fields.len() + 1;
+                let end_of_line = c == '\n' || (c == '\r' && chars.peek().is_none_or(|&n| n == '\n'));
+                state = match state {
+                    State::Quoted if c == '"' => State::QuoteInQuoted,
+                    State::Quoted => {
+                        field.push(c);
+                        State::Quoted
+                    }
+                    State::QuoteInQuoted if c == '"' => {
+                        field.push('"');
+                        State::Quoted
+                    }
+                    State::StartField if c == '"' => State::Quoted,
+                    State::Unquoted if c == '"' => {
+                        return Err(CsvError { row, column, kind: CsvErrorKind::UnexpectedQuote });
+                    }
+                    _ if c == self.delimiter => {
+                        fields.push(std::mem::take(&mut field));
+                        State::StartField
+                    }
+                    _ if end_of_line => {
+                        fields.push(field);
+                        return Ok(Some((row, fields)));
+                    }
+                    State::QuoteInQuoted => {
+                        return Err(CsvError { row, column, kind: CsvErrorKind::TrailingCharacter(c) });
+                    }
+                    _ => {
+                        field.push(c);
+                        State::Unquoted
+                    }
+                };
+            }
+
+            // 改行のないまま入力が終わった
+            if state != State::Quoted {
+                fields.push(field);
+                return Ok(Some((row, fields)));
+            }
+            // 引用符の中で行が終わったので、改行を含めて次の行に続ける
+            if self.read_line(&mut line)? == 0 {
+                return Err(CsvError { row, column: fields.len() + 1, kind: CsvErrorKind::UnterminatedQuote });
+            }
+        }
+    }
+}
+
+impl<R> Iterator for Reader<R>
+    where R: BufRead
+{
+    type Item = Result<Record, CsvError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if let Err(e) = self.headers() {
+            self.done = true;
+            return Some(Err(e));
+        }
+        match self.read_record() {
+            Ok(Some((row, fields))) => Some(Ok(Record { row, fields, headers: self.headers.clone() })),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
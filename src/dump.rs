@@ -0,0 +1,22 @@
+use std::fmt::Debug;
+use std::io::{self, Write};
+
+// イテレート可能なものなら何でも受け取り、各アイテムを1行ずつ表示する
+pub fn dump<T, U>(t: T)
+    where T: IntoIterator<Item=U>,
+          U: Debug
+{
+    dump_to(&mut io::stdout(), t).unwrap();
+}
+
+// dump()の出力先を指定できるもの
+pub fn dump_to<T, U>(out: &mut dyn Write, t: T) -> io::Result<()>
+    where T: IntoIterator<Item=U>,
+          U: Debug
+{
+    writeln!(out, "Dump via 'dump' function:")?;
+    for u in t {
+        writeln!(out, "{:?}", u)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use std::io::{self, Write};
+use std::iter::{once, repeat, repeat_n};
+
+// 1から始まる無限のfizz-buzz列。書籍どおりrepeat().take()でサイクルを作る
+#[allow(clippy::manual_repeat_n)]
+pub fn fizz_buzz() -> impl Iterator<Item=String> {
+    let fizzes = repeat("").take(2).chain(once("fizz")).cycle();
+    let buzzes = repeat("").take(4).chain(once("buzz")).cycle();
+    let fizzes_bazzes = fizzes.zip(buzzes);
+    (1..).zip(fizzes_bazzes)
+        .map(|tuple|
+            match tuple {
+                (i, ("", "")) => i.to_string(),
+                (_, (fizz, buzz)) => format!("{}{}", fizz, buzz)
+            })
+}
+
+// 数nに対して語を出すかどうかの規則
+enum Rule {
+    // period個ごとに1回
+    Period(usize, String),
+    // testが真を返す数で
+    Predicate(String, Box<dyn Fn(u64) -> bool>)
+}
+
+// fizz_buzz()を一般化したもの。規則ごとの語の列をzip()でまとめ、
+// 登録した順に語をつなげる。どの規則も語を出さない数はそのまま表示する
+#[derive(Default)]
+pub struct FizzBuzz {
+    rules: Vec<Rule>
+}
+
+impl FizzBuzz {
+    pub fn new() -> Self {
+        FizzBuzz { rules: Vec::new() }
+    }
+
+    // fizz_buzz()と同じ規則
+    pub fn standard() -> Self {
+        FizzBuzz::new().rule(3, "fizz").rule(5, "buzz")
+    }
+
+    pub fn rule(mut self, period: usize, word: &str) -> Self {
+        assert!(period > 0, "period must be positive");
+        self.rules.push(Rule::Period(period, word.to_string()));
+        self
+    }
+
+    pub fn predicate<F>(mut self, word: &str, test: F) -> Self
+        where F: Fn(u64) -> bool + 'static
+    {
+        self.rules.push(Rule::Predicate(word.to_string(), Box::new(test)));
+        self
+    }
+
+    // 1つの規則が1, 2, 3, ...に対して出す語の列
+    fn words<'a>(&'a self, rule: &'a Rule) -> Box<dyn Iterator<Item=&'a str> + 'a> {
+        match *rule {
+            Rule::Period(period, ref word) => {
+                Box::new(repeat_n("", period - 1).chain(once(word.as_str())).cycle())
+            }
+            Rule::Predicate(ref word, ref test) => {
+                Box::new((1..).map(move |n| if test(n) { word.as_str() } else { "" }))
+            }
+        }
+    }
+
+    // 1から始まる無限の列
+    pub fn iter(&self) -> impl Iterator<Item=String> + '_ {
+        let empty: Box<dyn Iterator<Item=String>> = Box::new(repeat(String::new()));
+        let joined = self.rules.iter().fold(empty, |joined, rule| {
+            Box::new(joined.zip(self.words(rule)).map(|(mut line, word)| {
+                line.push_str(word);
+                line
+            }))
+        });
+        (1u64..).zip(joined)
+            .map(|(i, line)| if line.is_empty() { i.to_string() } else { line })
+    }
+
+    // 1からcountまでを1行ずつ書き出す
+    pub fn write_to(&self, out: &mut dyn Write, count: usize) -> io::Result<()> {
+        for line in self.iter().take(count) {
+            writeln!(out, "{}", line)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+// next()が呼ばれて最後の要素に到達しても常にNoneを返さないイテレータ実装
+// fuse()アダプタで、最後の要素に到達したら常にNoneを返すイテレータに変換できる
+#[derive(Debug, Clone)]
+pub struct Flakey(pub bool);
+
+impl Iterator for Flakey {
+    type Item = &'static str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0 {
+            self.0 = false;
+            Some("totaly the last item")
+        } else {
+            self.0 = true;
+            None
+        }
+    }
+}
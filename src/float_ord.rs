@@ -0,0 +1,146 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+// IEEE 754のtotalOrderで全順序を付けたf64
+// -NaN < -∞ < ... < -0.0 < +0.0 < ... < +∞ < +NaN の順に並ぶので、Ordが必要な
+// max()、min()、sort()、BTreeMapのキーなどにそのまま使える
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TotalF64(pub f64);
+
+impl TotalF64 {
+    pub fn into_inner(self) -> f64 {
+        self.0
+    }
+
+    // 符号ビットが立っていれば残りのビットを反転させると、整数として比べた順序が
+    // totalOrderと一致する
+    fn key(self) -> i64 {
+        let bits = self.0.to_bits() as i64;
+        bits ^ (((bits >> 63) as u64) >> 1) as i64
+    }
+}
+
+impl From<f64> for TotalF64 {
+    fn from(value: f64) -> Self {
+        TotalF64(value)
+    }
+}
+
+impl From<TotalF64> for f64 {
+    fn from(value: TotalF64) -> Self {
+        value.0
+    }
+}
+
+impl Ord for TotalF64 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl PartialOrd for TotalF64 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Ordと矛盾しないように、ビット列が同じときだけ等しいとみなす
+// そのためNaN同士は等しくなり、-0.0と+0.0は等しくならない
+impl PartialEq for TotalF64 {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for TotalF64 {}
+
+impl Hash for TotalF64 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+impl fmt::Display for TotalF64 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+// max_by()/min_by()/sort_by()にそのまま渡せる比較用関数
+// partial_cmp(...).unwrap()と違い、NaNが与えられてもpanicしない
+pub fn total_cmp(lhs: &f64, rhs: &f64) -> Ordering {
+    TotalF64(*lhs).cmp(&TotalF64(*rhs))
+}
+
+// スライスをtotalOrderで並べ替える
+pub fn sort_floats(values: &mut [f64]) {
+    values.sort_by(total_cmp);
+}
+
+// max_by_float()/min_by_float()でNaNに出会ったときの扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NanPolicy {
+    // NaNを読み飛ばし、残りのアイテムから選ぶ
+    Skip,
+    // 最初のNaNを結果として返す（f64の演算と同じくNaNを伝播させる）
+    Propagate,
+    // 最初のNaNの位置をエラーとして返す
+    Error
+}
+
+// NanPolicy::ErrorでNaNに出会った位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NanError {
+    pub index: usize
+}
+
+impl fmt::Display for NanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "NaN at index {}", self.index)
+    }
+}
+
+impl std::error::Error for NanError {}
+
+// NaNの扱いを指定して浮動小数点数の最大と最小を求めるアダプタ
+// NaN以外の値の比較にはtotalOrderを使うので、-0.0より+0.0の方が大きい
+pub trait FloatOrdExt: Iterator + Sized
+    where Self::Item: Borrow<f64>
+{
+    fn max_by_float(self, policy: NanPolicy) -> Result<Option<f64>, NanError> {
+        select(self, policy, Ordering::Greater)
+    }
+
+    fn min_by_float(self, policy: NanPolicy) -> Result<Option<f64>, NanError> {
+        select(self, policy, Ordering::Less)
+    }
+}
+
+impl<I> FloatOrdExt for I
+    where I: Iterator,
+          I::Item: Borrow<f64>
+{}
+
+// wantedの向きに最も大きい（小さい）値を選ぶ
+fn select<I>(iter: I, policy: NanPolicy, wanted: Ordering) -> Result<Option<f64>, NanError>
+    where I: Iterator,
+          I::Item: Borrow<f64>
+{
+    let mut best: Option<f64> = None;
+    for (index, item) in iter.enumerate() {
+        let value = *item.borrow();
+        if value.is_nan() {
+            match policy {
+                NanPolicy::Skip => continue,
+                NanPolicy::Propagate => return Ok(Some(value)),
+                NanPolicy::Error => return Err(NanError { index })
+            }
+        }
+        if best.is_none_or(|b| total_cmp(&value, &b) == wanted) {
+            best = Some(value);
+        }
+    }
+    Ok(best)
+}
@@ -0,0 +1,130 @@
+use std::fmt;
+use std::iter::Peekable;
+
+// parse_float()が失敗した時のエラー
+// positionはparse_float()を呼び出した時点からの文字数（0始まり）で、不正な文字の位置を指す
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseFloatError {
+    pub position: usize,
+    pub expected: &'static str,
+    pub found: Option<char>
+}
+
+impl fmt::Display for ParseFloatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.found {
+            Some(c) => write!(f, "expected {} at position {}, found {:?}", self.expected, self.position, c),
+            None => write!(f, "expected {} at position {}, found end of input", self.expected, self.position)
+        }
+    }
+}
+
+impl std::error::Error for ParseFloatError {}
+
+// Peekable<I>から1文字ずつ読み進めつつ、読んだ文字を字句として貯めておくための状態
+struct Scanner<'a, I>
+    where I: Iterator<Item=char>
+{
+    tokens: &'a mut Peekable<I>,
+    lexeme: String,
+    position: usize
+}
+
+impl<'a, I> Scanner<'a, I>
+    where I: Iterator<Item=char>
+{
+    fn peek(&mut self) -> Option<char> {
+        self.tokens.peek().cloned()
+    }
+
+    fn bump(&mut self) {
+        if let Some(c) = self.tokens.next() {
+            self.lexeme.push(c);
+            self.position += 1;
+        }
+    }
+
+    // 次の文字がpredicateを満たす間だけ消費し、消費した文字数を返す
+    fn bump_while<P>(&mut self, predicate: P) -> usize
+        where P: Fn(char) -> bool
+    {
+        let mut n = 0;
+        while let Some(c) = self.peek() {
+            if !predicate(c) {
+                break;
+            }
+            self.bump();
+            n += 1;
+        }
+        n
+    }
+
+    fn error(&mut self, expected: &'static str) -> ParseFloatError {
+        ParseFloatError { position: self.position, expected, found: self.peek() }
+    }
+
+    // 大文字小文字を区別せずにwordを読む
+    fn expect_word(&mut self, word: &'static str) -> Result<(), ParseFloatError> {
+        for w in word.chars() {
+            match self.peek() {
+                Some(c) if c.eq_ignore_ascii_case(&w) => self.bump(),
+                _ => return Err(self.error(word))
+            }
+        }
+        Ok(())
+    }
+}
+
+// parse_number()の浮動小数点数版
+// 符号、小数部、指数部、inf/infinity/nan（大文字小文字は区別しない）を読み取り、
+// 数値の続きになり得ない文字に出会ったところで止まる（その文字は消費しない）
+// 丸めはf64::from_str()に任せるため、結果は常にf64::from_str()と一致する
+pub fn parse_float<I>(tokens: &mut Peekable<I>) -> Result<f64, ParseFloatError>
+    where I: Iterator<Item=char>
+{
+    let mut s = Scanner { tokens, lexeme: String::new(), position: 0 };
+
+    if let Some('+') | Some('-') = s.peek() {
+        s.bump();
+    }
+
+    match s.peek() {
+        Some(c) if c == 'i' || c == 'I' => {
+            s.expect_word("inf")?;
+            if let Some(c) = s.peek() {
+                if c == 'i' || c == 'I' {
+                    s.expect_word("inity")?;
+                }
+            }
+            return Ok(s.lexeme.parse().unwrap());
+        }
+        Some(c) if c == 'n' || c == 'N' => {
+            s.expect_word("nan")?;
+            return Ok(s.lexeme.parse().unwrap());
+        }
+        _ => {}
+    }
+
+    // 整数部と小数部のどちらかには少なくとも1桁の数字が必要（"1." や ".5" は許す）
+    let mut digits = s.bump_while(|c| c.is_ascii_digit());
+    if s.peek() == Some('.') {
+        s.bump();
+        digits += s.bump_while(|c| c.is_ascii_digit());
+    }
+    if digits == 0 {
+        return Err(s.error("digit"));
+    }
+
+    if let Some('e') | Some('E') = s.peek() {
+        s.bump();
+        if let Some('+') | Some('-') = s.peek() {
+            s.bump();
+        }
+        if s.bump_while(|c| c.is_ascii_digit()) == 0 {
+            return Err(s.error("exponent digit"));
+        }
+    }
+
+    // ここまでで字句はf64::from_str()が受け付ける形式になっている
+    Ok(s.lexeme.parse().unwrap())
+}
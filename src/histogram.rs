@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+use std::iter::FromIterator;
+
+// 値をどの区間に振り分けるか
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Buckets {
+    // [k * width, (k + 1) * width)の等間隔の区間
+    Fixed { width: f64 },
+    // [base^k, base^(k + 1))の対数の区間。正の値だけを数え、0以下の値はunderflowに数える
+    Log { base: f64 }
+}
+
+// 1つの区間 [low, high) とそこに入った値の数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bucket {
+    pub low: f64,
+    pub high: f64,
+    pub count: u64
+}
+
+// 値が入った区間だけを持つヒストグラム。範囲を前もって決める必要はない
+// collect()で作るとBuckets::Log { base: 2.0 }になる
+// 区間の番号がi64に収まらない値（±infを含む）は、区間より上ならoverflowに、下ならunderflowに数える
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    buckets: Buckets,
+    counts: BTreeMap<i64, u64>,
+    underflow: u64,
+    overflow: u64,
+    nan: u64
+}
+
+impl Histogram {
+    pub fn new(buckets: Buckets) -> Self {
+        match buckets {
+            Buckets::Fixed { width } => assert!(width > 0.0 && width.is_finite(), "invalid width: {}", width),
+            Buckets::Log { base } => assert!(base > 1.0 && base.is_finite(), "invalid base: {}", base)
+        }
+        Histogram { buckets, counts: BTreeMap::new(), underflow: 0, overflow: 0, nan: 0 }
+    }
+
+    pub fn fixed(width: f64) -> Self {
+        Histogram::new(Buckets::Fixed { width })
+    }
+
+    pub fn log(base: f64) -> Self {
+        Histogram::new(Buckets::Log { base })
+    }
+
+    // k番目の区間の下限
+    fn bound(&self, k: i64) -> f64 {
+        match self.buckets {
+            Buckets::Fixed { width } => k as f64 * width,
+            Buckets::Log { base } => base.powf(k as f64)
+        }
+    }
+
+    // 丸め誤差で隣の区間に入らないよう、bound()と同じ計算で確かめてから決める
+    // 番号がi64に収まらない値や、大きすぎて区間の上限と下限が区別できない値はNone
+    fn key(&self, value: f64) -> Option<i64> {
+        let index = match self.buckets {
+            Buckets::Fixed { width } => (value / width).floor(),
+            Buckets::Log { base } => (value.ln() / base.ln()).floor()
+        };
+        // asでのキャストは飽和してしまうので、その前に範囲を確かめる
+        if !(index > i64::MIN as f64 && index < i64::MAX as f64) {
+            return None;
+        }
+        let mut k = index as i64;
+        while k > i64::MIN && self.bound(k) > value {
+            k -= 1;
+        }
+        while k < i64::MAX && self.bound(k + 1) <= value {
+            k += 1;
+        }
+        let next = k.checked_add(1)?;
+        if self.bound(k) <= value && value < self.bound(next) { Some(k) } else { None }
+    }
+
+    pub fn insert(&mut self, value: f64) {
+        if value.is_nan() {
+            self.nan += 1;
+        } else if matches!(self.buckets, Buckets::Log { .. }) && value <= 0.0 {
+            self.underflow += 1;
+        } else {
+            match self.key(value) {
+                Some(k) => *self.counts.entry(k).or_insert(0) += 1,
+                None if value > self.bound(0) => self.overflow += 1,
+                None => self.underflow += 1
+            }
+        }
+    }
+
+    // 区間に振り分けた値の数（underflow、overflowとNaNは含まない）
+    pub fn count(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    pub fn underflow(&self) -> u64 {
+        self.underflow
+    }
+
+    pub fn overflow(&self) -> u64 {
+        self.overflow
+    }
+
+    pub fn nan_count(&self) -> u64 {
+        self.nan
+    }
+
+    // 値が入った区間を小さい順に返す
+    pub fn buckets(&self) -> impl Iterator<Item=Bucket> + '_ {
+        self.counts.iter().map(move |(&k, &count)| {
+            Bucket { low: self.bound(k), high: self.bound(k + 1), count }
+        })
+    }
+
+    // 区間に振り分けた値のうち、φ分位点が入っている区間
+    // 真の値はlow以上high未満にあるので、Logなら相対誤差はbase - 1以下になる
+    pub fn quantile(&self, phi: f64) -> Option<Bucket> {
+        let count = self.count();
+        if count == 0 {
+            return None;
+        }
+        let rank = ((phi.clamp(0.0, 1.0) * count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        self.buckets().find(|bucket| {
+            seen += bucket.count;
+            seen >= rank
+        })
+    }
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Histogram::log(2.0)
+    }
+}
+
+impl Extend<f64> for Histogram {
+    fn extend<T: IntoIterator<Item=f64>>(&mut self, iter: T) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<'a> Extend<&'a f64> for Histogram {
+    fn extend<T: IntoIterator<Item=&'a f64>>(&mut self, iter: T) {
+        self.extend(iter.into_iter().cloned());
+    }
+}
+
+impl FromIterator<f64> for Histogram {
+    fn from_iter<T: IntoIterator<Item=f64>>(iter: T) -> Self {
+        let mut histogram = Histogram::default();
+        histogram.extend(iter);
+        histogram
+    }
+}
+
+impl<'a> FromIterator<&'a f64> for Histogram {
+    fn from_iter<T: IntoIterator<Item=&'a f64>>(iter: T) -> Self {
+        iter.into_iter().cloned().collect()
+    }
+}
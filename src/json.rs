@@ -0,0 +1,504 @@
+use std::fmt;
+use std::io::{self, BufReader, Read};
+
+// JSONを読み進めるごとに生成されるイベント
+// DOMを作らずにイベントを順に取り出すので、巨大なファイルでも一定のメモリで処理できる
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    StartObject,
+    EndObject,
+    StartArray,
+    EndArray,
+    Key(String),
+    String(String),
+    Number(f64),
+    Bool(bool),
+    Null
+}
+
+// 入力中の位置（offsetはバイト単位、lineとcolumnは1始まりでcolumnは文字単位）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize
+}
+
+#[derive(Debug)]
+pub enum ErrorKind {
+    Io(io::Error),
+    UnexpectedByte(u8),
+    UnexpectedEof,
+    InvalidNumber,
+    InvalidEscape,
+    InvalidUtf8,
+    ControlCharacter,
+    DepthLimitExceeded(usize),
+    TrailingData
+}
+
+#[derive(Debug)]
+pub struct JsonError {
+    pub position: Position,
+    pub kind: ErrorKind
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ErrorKind::Io(ref e) => write!(f, "I/O error: {}", e)?,
+            ErrorKind::UnexpectedByte(b) if b.is_ascii() => write!(f, "unexpected character {:?}", b as char)?,
+            ErrorKind::UnexpectedByte(b) => write!(f, "unexpected byte 0x{:02x}", b)?,
+            ErrorKind::UnexpectedEof => write!(f, "unexpected end of input")?,
+            ErrorKind::InvalidNumber => write!(f, "invalid number")?,
+            ErrorKind::InvalidEscape => write!(f, "invalid escape sequence")?,
+            ErrorKind::InvalidUtf8 => write!(f, "invalid UTF-8 in string")?,
+            ErrorKind::ControlCharacter => write!(f, "control character in string")?,
+            ErrorKind::DepthLimitExceeded(limit) => write!(f, "nesting deeper than {}", limit)?,
+            ErrorKind::TrailingData => write!(f, "trailing data after JSON value")?
+        }
+        write!(f, " at line {}, column {}", self.position.line, self.position.column)
+    }
+}
+
+impl std::error::Error for JsonError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self.kind {
+            ErrorKind::Io(ref e) => Some(e),
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Container {
+    Object,
+    Array
+}
+
+// 次に何を読むべきかを表す状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    // トップレベルの値
+    Start,
+    // '['の直後。値か']'
+    ArrayFirst,
+    // 配列の','の直後。値
+    ArrayNext,
+    // '{'の直後。キーか'}'
+    ObjectFirst,
+    // オブジェクトの','の直後。キー
+    ObjectNext,
+    // キーの直後。':'と値
+    ObjectValue,
+    // 値を読み終えた直後。','か閉じ括弧、トップレベルなら入力の終わり
+    AfterValue,
+    // 入力の終わりに達したか、エラーを返した後
+    Finished
+}
+
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+// バイト列からJSONのイベントを1つずつ取り出すイテレータ
+// エラーを返した後は常にNoneを返す
+pub struct JsonReader<B>
+    where B: Iterator<Item=io::Result<u8>>
+{
+    bytes: B,
+    peeked: Option<u8>,
+    position: Position,
+    stack: Vec<Container>,
+    state: State,
+    max_depth: usize,
+    last_was_start: bool
+}
+
+pub type StrBytes<'a> = std::iter::Map<std::str::Bytes<'a>, fn(u8) -> io::Result<u8>>;
+
+// &strから読み込むJsonReaderを作る
+pub fn from_str(text: &str) -> JsonReader<StrBytes<'_>> {
+    JsonReader::new(text.bytes().map(Ok as fn(u8) -> io::Result<u8>))
+}
+
+// io::Readから読み込むJsonReaderを作る。読み込みはBufReaderでバッファリングされる
+pub fn from_reader<R>(reader: R) -> JsonReader<io::Bytes<BufReader<R>>>
+    where R: Read
+{
+    JsonReader::new(BufReader::new(reader).bytes())
+}
+
+impl<B> JsonReader<B>
+    where B: Iterator<Item=io::Result<u8>>
+{
+    pub fn new(bytes: B) -> JsonReader<B> {
+        JsonReader {
+            bytes,
+            peeked: None,
+            position: Position { offset: 0, line: 1, column: 1 },
+            stack: Vec::new(),
+            state: State::Start,
+            max_depth: DEFAULT_MAX_DEPTH,
+            last_was_start: false
+        }
+    }
+
+    // 入れ子の深さの上限を変更する
+    pub fn max_depth(mut self, max_depth: usize) -> JsonReader<B> {
+        self.max_depth = max_depth;
+        self
+    }
+
+    // 次のバイトの位置
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    // 現在の入れ子の深さ
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    fn error(&self, kind: ErrorKind) -> JsonError {
+        JsonError { position: self.position, kind }
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>, JsonError> {
+        if self.peeked.is_none() {
+            match self.bytes.next() {
+                Some(Ok(b)) => self.peeked = Some(b),
+                Some(Err(e)) => return Err(self.error(ErrorKind::Io(e))),
+                None => return Ok(None)
+            }
+        }
+        Ok(self.peeked)
+    }
+
+    fn bump(&mut self) -> Result<Option<u8>, JsonError> {
+        let b = self.peek()?;
+        if let Some(b) = b {
+            self.peeked = None;
+            self.position.offset += 1;
+            if b == b'\n' {
+                self.position.line += 1;
+                self.position.column = 1;
+            } else if b & 0xC0 != 0x80 {
+                // UTF-8の継続バイトでは列を進めない
+                self.position.column += 1;
+            }
+        }
+        Ok(b)
+    }
+
+    // 次のバイトを読み、なければUnexpectedEofとする
+    fn bump_required(&mut self) -> Result<u8, JsonError> {
+        match self.bump()? {
+            Some(b) => Ok(b),
+            None => Err(self.error(ErrorKind::UnexpectedEof))
+        }
+    }
+
+    fn skip_whitespace(&mut self) -> Result<Option<u8>, JsonError> {
+        while let Some(b) = self.peek()? {
+            match b {
+                b' ' | b'\t' | b'\n' | b'\r' => { self.bump()?; }
+                _ => return Ok(Some(b))
+            }
+        }
+        Ok(None)
+    }
+
+    fn unexpected(&mut self) -> JsonError {
+        match self.peeked {
+            Some(b) => self.error(ErrorKind::UnexpectedByte(b)),
+            None => self.error(ErrorKind::UnexpectedEof)
+        }
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<(), JsonError> {
+        match self.skip_whitespace()? {
+            Some(b) if b == expected => {
+                self.bump()?;
+                Ok(())
+            }
+            _ => Err(self.unexpected())
+        }
+    }
+
+    fn literal(&mut self, word: &'static [u8], event: Event) -> Result<Event, JsonError> {
+        for &w in word {
+            match self.peek()? {
+                Some(b) if b == w => { self.bump()?; }
+                _ => return Err(self.unexpected())
+            }
+        }
+        self.state = State::AfterValue;
+        Ok(event)
+    }
+
+    fn push(&mut self, container: Container) -> Result<(), JsonError> {
+        if self.stack.len() >= self.max_depth {
+            return Err(self.error(ErrorKind::DepthLimitExceeded(self.max_depth)));
+        }
+        self.bump()?;
+        self.stack.push(container);
+        self.state = match container {
+            Container::Object => State::ObjectFirst,
+            Container::Array => State::ArrayFirst
+        };
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<Event, JsonError> {
+        self.bump()?;
+        self.state = State::AfterValue;
+        match self.stack.pop() {
+            Some(Container::Object) => Ok(Event::EndObject),
+            Some(Container::Array) => Ok(Event::EndArray),
+            None => unreachable!()
+        }
+    }
+
+    fn value(&mut self) -> Result<Event, JsonError> {
+        match self.skip_whitespace()? {
+            Some(b'{') => {
+                self.push(Container::Object)?;
+                Ok(Event::StartObject)
+            }
+            Some(b'[') => {
+                self.push(Container::Array)?;
+                Ok(Event::StartArray)
+            }
+            Some(b'"') => {
+                let s = self.string()?;
+                self.state = State::AfterValue;
+                Ok(Event::String(s))
+            }
+            Some(b't') => self.literal(b"true", Event::Bool(true)),
+            Some(b'f') => self.literal(b"false", Event::Bool(false)),
+            Some(b'n') => self.literal(b"null", Event::Null),
+            Some(b'-') | Some(b'0'..=b'9') => {
+                let n = self.number()?;
+                self.state = State::AfterValue;
+                Ok(Event::Number(n))
+            }
+            _ => Err(self.unexpected())
+        }
+    }
+
+    fn key(&mut self) -> Result<Event, JsonError> {
+        match self.skip_whitespace()? {
+            Some(b'"') => {
+                let key = self.string()?;
+                self.state = State::ObjectValue;
+                Ok(Event::Key(key))
+            }
+            _ => Err(self.unexpected())
+        }
+    }
+
+    fn digits(&mut self, text: &mut String) -> Result<usize, JsonError> {
+        let mut n = 0;
+        while let Some(b) = self.peek()? {
+            if !b.is_ascii_digit() {
+                break;
+            }
+            text.push(b as char);
+            self.bump()?;
+            n += 1;
+        }
+        Ok(n)
+    }
+
+    // -? (0 | [1-9][0-9]*) (. [0-9]+)? ([eE] [+-]? [0-9]+)?
+    fn number(&mut self) -> Result<f64, JsonError> {
+        let start = self.position;
+        let mut text = String::new();
+        if self.peek()? == Some(b'-') {
+            text.push('-');
+            self.bump()?;
+        }
+        match self.peek()? {
+            Some(b'0') => {
+                text.push('0');
+                self.bump()?;
+            }
+            Some(b'1'..=b'9') => { self.digits(&mut text)?; }
+            _ => return Err(self.unexpected())
+        }
+        if self.peek()? == Some(b'.') {
+            text.push('.');
+            self.bump()?;
+            if self.digits(&mut text)? == 0 {
+                return Err(self.unexpected());
+            }
+        }
+        if let Some(e) = self.peek()?.filter(|&b| b == b'e' || b == b'E') {
+            text.push(e as char);
+            self.bump()?;
+            if let Some(sign) = self.peek()?.filter(|&b| b == b'+' || b == b'-') {
+                text.push(sign as char);
+                self.bump()?;
+            }
+            if self.digits(&mut text)? == 0 {
+                return Err(self.unexpected());
+            }
+        }
+        text.parse().map_err(|_| JsonError { position: start, kind: ErrorKind::InvalidNumber })
+    }
+
+    fn hex4(&mut self) -> Result<u32, JsonError> {
+        let mut n = 0;
+        for _ in 0..4 {
+            let position = self.position;
+            let b = self.bump_required()?;
+            let digit = (b as char).to_digit(16)
+                .ok_or(JsonError { position, kind: ErrorKind::InvalidEscape })?;
+            n = n * 16 + digit;
+        }
+        Ok(n)
+    }
+
+    // 開きの'"'から閉じの'"'までを読み、エスケープを解決した文字列を返す
+    fn string(&mut self) -> Result<String, JsonError> {
+        let start = self.position;
+        self.bump()?;
+        let mut bytes = Vec::new();
+        loop {
+            let position = self.position;
+            match self.bump_required()? {
+                b'"' => break,
+                b'\\' => {
+                    let c = match self.bump_required()? {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => self.unicode_escape(position)?,
+                        _ => return Err(JsonError { position, kind: ErrorKind::InvalidEscape })
+                    };
+                    let mut buf = [0; 4];
+                    bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                }
+                b if b < 0x20 => return Err(JsonError { position, kind: ErrorKind::ControlCharacter }),
+                b => bytes.push(b)
+            }
+        }
+        String::from_utf8(bytes).map_err(|_| JsonError { position: start, kind: ErrorKind::InvalidUtf8 })
+    }
+
+    // "\u"の後の4桁を読む。サロゲートペアは続く"\uXXXX"と組み合わせる
+    fn unicode_escape(&mut self, position: Position) -> Result<char, JsonError> {
+        let invalid = JsonError { position, kind: ErrorKind::InvalidEscape };
+        let high = self.hex4()?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if self.bump_required()? != b'\\' || self.bump_required()? != b'u' {
+                return Err(invalid);
+            }
+            let low = self.hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(invalid);
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+        std::char::from_u32(code).ok_or(invalid)
+    }
+
+    fn read_event(&mut self) -> Result<Option<Event>, JsonError> {
+        loop {
+            let event = match self.state {
+                State::Finished => return Ok(None),
+                State::Start | State::ArrayNext => self.value()?,
+                State::ObjectNext => self.key()?,
+                State::ObjectValue => {
+                    self.expect(b':')?;
+                    self.value()?
+                }
+                State::ArrayFirst => match self.skip_whitespace()? {
+                    Some(b']') => self.pop()?,
+                    _ => self.value()?
+                },
+                State::ObjectFirst => match self.skip_whitespace()? {
+                    Some(b'}') => self.pop()?,
+                    _ => self.key()?
+                },
+                State::AfterValue => {
+                    let next = self.skip_whitespace()?;
+                    match (self.stack.last(), next) {
+                        (None, None) => {
+                            self.state = State::Finished;
+                            return Ok(None);
+                        }
+                        (None, Some(_)) => return Err(self.error(ErrorKind::TrailingData)),
+                        (Some(&Container::Array), Some(b']')) | (Some(&Container::Object), Some(b'}')) => self.pop()?,
+                        (Some(&container), Some(b',')) => {
+                            self.bump()?;
+                            self.state = match container {
+                                Container::Array => State::ArrayNext,
+                                Container::Object => State::ObjectNext
+                            };
+                            continue;
+                        }
+                        _ => return Err(self.unexpected())
+                    }
+                }
+            };
+            self.last_was_start = event == Event::StartObject || event == Event::StartArray;
+            return Ok(Some(event));
+        }
+    }
+
+    // 次に閉じ括弧が来るかどうか（読み飛ばす値が残っていないかどうか）
+    fn at_container_end(&mut self) -> Result<bool, JsonError> {
+        match self.state {
+            State::ArrayFirst | State::ObjectFirst | State::AfterValue if !self.stack.is_empty() => {
+                Ok(matches!(self.skip_whitespace()?, Some(b']') | Some(b'}')))
+            }
+            _ => Ok(false)
+        }
+    }
+
+    // 部分木を読み飛ばす
+    // StartObject/StartArrayを受け取った直後なら対応するEndObject/EndArrayまでを、
+    // Keyを受け取った直後ならその値を、それ以外では次の値（オブジェクトならキーと値の組）を読み飛ばす
+    pub fn skip_value(&mut self) -> Result<(), JsonError> {
+        let mut depth = if self.last_was_start { 1 } else { 0 };
+        if depth == 0 && self.at_container_end()? {
+            return Ok(());
+        }
+        for event in self.by_ref() {
+            match event? {
+                Event::StartObject | Event::StartArray => depth += 1,
+                Event::EndObject | Event::EndArray => depth -= 1,
+                Event::Key(_) => continue,
+                _ => {}
+            }
+            if depth == 0 {
+                break;
+            }
+        }
+        self.last_was_start = false;
+        Ok(())
+    }
+}
+
+impl<B> Iterator for JsonReader<B>
+    where B: Iterator<Item=io::Result<u8>>
+{
+    type Item = Result<Event, JsonError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.read_event() {
+            Ok(event) => event.map(Ok),
+            Err(e) => {
+                self.state = State::Finished;
+                Some(Err(e))
+            }
+        }
+    }
+}
@@ -0,0 +1,944 @@
+// デモ用のレッスン一覧。各レッスンは結果をoutに書き出し、assertで動作を確かめる
+// 書籍の説明どおりの書き方（あえて冗長なものや警告が出るものを含む）を残しているレッスンでは、
+// それに対するlintだけをそのレッスンで許可する
+
+use std::collections::{BTreeSet, HashMap};
+use std::io::{self, Write};
+use std::iter::FromIterator;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::dump::dump_to;
+
+pub struct Lesson {
+    pub name: &'static str,
+    pub topic: &'static str,
+    pub run: fn(&mut dyn Write) -> io::Result<()>
+}
+
+pub fn all() -> &'static [Lesson] {
+    LESSONS
+}
+
+pub fn find(name: &str) -> Option<&'static Lesson> {
+    LESSONS.iter().find(|lesson| lesson.name == name)
+}
+
+// 登場する順に並べた全てのトピック
+pub fn topics() -> Vec<&'static str> {
+    let mut topics: Vec<&str> = Vec::new();
+    for lesson in LESSONS {
+        if !topics.contains(&lesson.topic) {
+            topics.push(lesson.topic);
+        }
+    }
+    topics
+}
+
+macro_rules! lessons {
+    ($($topic:expr => [$($name:expr => $run:ident),*]),*) => {
+        const LESSONS: &[Lesson] = &[
+            $($(Lesson { name: $name, topic: $topic, run: $run },)*)*
+        ];
+    }
+}
+
+lessons! {
+    "iteration" => [
+        "for-loop" => for_loop,
+        "dump" => dump,
+        "into-iter" => into_iter,
+        "drain" => drain,
+        "flakey" => flakey,
+        "double-ended" => double_ended,
+        "i32-range" => i32_range,
+        "series" => series,
+        "binary-tree" => binary_tree,
+        "sequences" => sequences
+    ],
+    "adapters" => [
+        "lazy-map" => lazy_map,
+        "map-filter" => map_filter,
+        "filter-map" => filter_map,
+        "flat-map" => flat_map,
+        "scan" => scan,
+        "take-while" => take_while,
+        "peekable" => peekable,
+        "inspect" => inspect,
+        "chain" => chain,
+        "enumerate" => enumerate,
+        "zip" => zip,
+        "cloned" => cloned,
+        "cycle" => cycle,
+        "fizz-buzz" => fizz_buzz,
+        "fizz-buzz-rules" => fizz_buzz_rules,
+        "schedule" => schedule
+    ],
+    "consumers" => [
+        "sum-product" => sum_product,
+        "overflow" => overflow,
+        "bigint" => bigint,
+        "max-min" => max_min,
+        "float-ord" => float_ord,
+        "stats" => stats,
+        "quantiles" => quantiles,
+        "max-by-key" => max_by_key,
+        "top-k" => top_k,
+        "compare" => compare,
+        "any-all" => any_all,
+        "position" => position,
+        "fold" => fold,
+        "nth-last" => nth_last,
+        "find" => find_population,
+        "extend" => extend,
+        "partition" => partition
+    ],
+    "parsers" => [
+        "parse-all" => parse_all,
+        "float-parse" => float_parse,
+        "lexer" => lexer,
+        "multipeek" => multipeek,
+        "combinator" => combinator
+    ],
+    "formats" => [
+        "message" => message,
+        "mime" => mime,
+        "mbox" => mbox,
+        "json" => json,
+        "csv" => csv
+    ],
+    "testing" => [
+        "contract" => contract
+    ]
+}
+
+#[allow(clippy::into_iter_on_ref, clippy::while_let_on_iterator)]
+fn for_loop(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "There is:")?;
+    let v = vec!["antimony", "arsenic", "alumium", "selenium"];
+    for element in &v {
+        writeln!(out, "{}", element)?;
+    }
+    // 上記のループは以下と同等
+    let mut iterator = (&v).into_iter();
+    while let Some(element) = iterator.next() {
+        // Some(element)が返されたらループボディ部を実行するがNoneが返されたら終了する
+        writeln!(out, "{}", element)?;
+    }
+    Ok(())
+}
+
+fn dump(out: &mut dyn Write) -> io::Result<()> {
+    let v = vec![4, 20, 12, 8, 6];
+    let mut iterator = v.iter();
+    assert_eq!(iterator.next(), Some(&4));
+    assert_eq!(iterator.nth(3), Some(&6));
+    // 最後の要素Noneを返したあとでさらにnext()を呼んだ時に何を返すかをIteratorトレイトは規定していないが、多くの実装では再度Noneを返す
+    assert_eq!(iterator.next(), None);
+    assert_eq!(iterator.next(), None);
+
+    // dump()はイテレート可能なものなら何でも受け取る
+    dump_to(out, v)?;
+    dump_to(out, Path::new("C:/Users/Jimb/Downloads/Fedra.iso"))
+}
+
+fn into_iter(out: &mut dyn Write) -> io::Result<()> {
+    // HashSetでなくイテレータの順序が保証されるBTreeSetを使う
+    let mut favorites = BTreeSet::new();
+    favorites.insert("Lucy in the Sky With Diamonds".to_string());
+    favorites.insert("Libebestramue No. 3".to_string());
+
+    // favorites.into_iter()は所有権ごと返すイテレータなので、この後favoritesは使えない
+    let mut it = favorites.into_iter();
+    assert_eq!(it.next(), Some("Libebestramue No. 3".to_string()));
+    assert_eq!(it.next(), Some("Lucy in the Sky With Diamonds".to_string()));
+    assert_eq!(it.next(), None);
+    writeln!(out, "favorites moved into the iterator")
+}
+
+fn drain(out: &mut dyn Write) -> io::Result<()> {
+    // 多くのコレクションはdrain()を実装しており、指定範囲の可変参照を借用したイテレータを返す
+    let mut outer = "Earth".to_string();
+    let inner = String::from_iter(outer.drain(1..4));
+
+    assert_eq!(outer, "Eh");
+    assert_eq!(inner, "art");
+    writeln!(out, "outer = {:?}, inner = {:?}", outer, inner)
+}
+
+fn flakey(out: &mut dyn Write) -> io::Result<()> {
+    use crate::flakey::Flakey;
+
+    // next()を呼んでNoneになっても再び要素が返される
+    let mut flaky = Flakey(true);
+    assert_eq!((flaky.next(), flaky.next(), flaky.next()),
+               (Some("totaly the last item"), None, Some("totaly the last item")));
+    writeln!(out, "flaky:     {:?} {:?} {:?}", flaky.next(), flaky.next(), flaky.next())?;
+
+    // fuseアダプタにより、最後の要素に到達したら常にNoneを返すイテレータに変換可能
+    let mut not_flaky = Flakey(true).fuse();
+    assert_eq!(not_flaky.next(), Some("totaly the last item"));
+    assert_eq!(not_flaky.next(), None);
+    assert_eq!(not_flaky.next(), None);
+    writeln!(out, "not flaky: {:?}", Flakey(true).fuse().take(3).collect::<Vec<_>>())
+}
+
+fn double_ended(out: &mut dyn Write) -> io::Result<()> {
+    let bee_parts = ["head", "thorax", "abdomen"];
+
+    // DoubleEndedIteratorを実装している型は前端と後端のアイテムを引き出せる
+    let mut iter = bee_parts.iter();
+    assert_eq!(iter.next(),      Some(&"head"));
+    assert_eq!(iter.next_back(), Some(&"abdomen"));
+    assert_eq!(iter.next(),      Some(&"thorax"));
+    // 2本の指が一致したところで繰り返し実行は終了する
+    assert_eq!(iter.next_back(), None);
+
+    // rev()でnext/next_backが反転したイテレータを取得できる
+    let meals = ["breakfast", "lunch", "dinner"];
+    for meal in meals.iter().rev() {
+        writeln!(out, "{}", meal)?;
+    }
+    Ok(())
+}
+
+fn i32_range(out: &mut dyn Write) -> io::Result<()> {
+    use crate::range::I32Range;
+
+    let mut pi = 0.0;
+    let mut numerator = 1.0;
+
+    // I32RangeはIteratorを実装しているためfor文で利用できる
+    for k in (I32Range { start: 0, end: 14 }) {
+        pi += numerator / (2*k + 1) as f64;
+        numerator /= -3.0;
+    }
+
+    pi *= f64::sqrt(12.0);
+
+    // IEEE754に準拠するとconsts::PIと完全に一致する
+    assert_eq!(pi as f32, std::f32::consts::PI);
+    writeln!(out, "pi = {}", pi as f32)
+}
+
+fn series(out: &mut dyn Write) -> io::Result<()> {
+    use crate::series::SeriesExt;
+
+    // ライプニッツの級数 1 - 1/3 + 1/5 - ... = π/4 はそのままでは収束が遅い
+    let leibniz = || (0..).map(|k| if k % 2 == 0 { 1.0 } else { -1.0 } / (2 * k + 1) as f64);
+    let plain = leibniz().partial_sums().nth(13).unwrap() * 4.0;
+    assert!((plain - std::f64::consts::PI).abs() > 0.05);
+
+    // 同じ14項から、Aitkenのδ²法を3回適用するとf32の精度に届く
+    let accelerated = leibniz().take(14).partial_sums().aitken().aitken().aitken().last().unwrap() * 4.0;
+    assert_eq!(accelerated as f32, std::f32::consts::PI);
+    writeln!(out, "plain = {}, aitken = {}", plain, accelerated)
+}
+
+fn binary_tree(out: &mut dyn Write) -> io::Result<()> {
+    use crate::tree::{make_node, BinaryTree};
+
+    let subtree_l = make_node(BinaryTree::Empty, "mecha", BinaryTree::Empty);
+    let subtree_rl = make_node(BinaryTree::Empty, "droid", BinaryTree::Empty);
+    let subtree_r = make_node(subtree_rl, "robot", BinaryTree::Empty);
+    let tree = make_node(subtree_l, "Jaeger", subtree_r);
+
+    // 左にあるノードから順にsubtree_l, tree, subtree_rl, subtree_rの順にイテレートされる
+    let v: Vec<&str> = tree.iter().cloned().collect();
+    assert_eq!(v, ["mecha", "Jaeger", "droid", "robot"]);
+
+    // 型BinaryTreeはアダプタが利用できる
+    for name in tree.iter().map(|name| format!("mega-{}", name)) {
+        writeln!(out, "{}", name)?;
+    }
+    Ok(())
+}
+
+fn sequences(out: &mut dyn Write) -> io::Result<()> {
+    use crate::sequences::{collatz, fibonacci, primes, triangular};
+
+    // 無限に続く数列も、必要な分だけ取り出せば使える
+    let first: Vec<u64> = primes().take(10).collect();
+    assert_eq!(first, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+    assert_eq!(primes().take_while(|&p| p < 100).count(), 25);
+
+    // nth()は途中の値を生成せずに求める
+    assert_eq!(fibonacci().nth(90), Some(2880067194370816120));
+    assert_eq!(triangular().nth(19), Some(210));
+
+    // 三角数のうち素数の隣にあるもの
+    let pairs: Vec<(u64, u64)> = triangular().zip(primes()).take(100).filter(|&(t, p)| t + 1 == p || t == p + 1).collect();
+    writeln!(out, "{:?}", pairs)?;
+    writeln!(out, "collatz(27) has {} steps", collatz(27).count() - 1)
+}
+
+#[allow(unused_must_use)]
+fn lazy_map(out: &mut dyn Write) -> io::Result<()> {
+    // iter()呼び出しだけでは値が要求されずnext()が呼ばれた時に初めて使われる
+    // このコードはコンパイル時に以下の警告を出す
+    // warning: unused `std::iter::Map` that must be used
+    // 最後に.next()をコールすると、クロージャが実行される
+    ["earth", "water", "air", "fire"]
+        .iter().map(|ert| writeln!(out, "{}", ert));
+    writeln!(out, "nothing printed above")
+}
+
+fn map_filter(out: &mut dyn Write) -> io::Result<()> {
+    // text.lines()で返された各行のイテレータをmap()アダプタで空白を除去する
+    let text = "  ponies  \n  giraffers\niguanas  \nsquid".to_string();
+    let v: Vec<&str> = text.lines()
+        .map(str::trim)
+        .filter(|s| *s != "iguanas") // trueを返すものだけをイテレータのアイテムとして生成する
+        .collect();
+    assert_eq!(v, ["ponies", "giraffers", "squid"]);
+    writeln!(out, "{:?}", v)
+}
+
+#[allow(clippy::redundant_closure, clippy::result_filter_map)]
+fn filter_map(out: &mut dyn Write) -> io::Result<()> {
+    let text = "1\nfrond .25 289\n3.1415 estuary\n";
+    // パースに失敗した単語はok()でNoneになり、filter_map()で取り除かれる
+    for number in text.split_whitespace()
+        .filter_map(|w| f64::from_str(w).ok()) {
+            writeln!(out, "{:4.2}", number.sqrt())?;
+        }
+
+    // 上のfilter_map()と同じ処理ををmap()->filter()->map()で書き直したもの
+    let numbers: Vec<f64> = text.split_whitespace()
+        .map(|w| f64::from_str(w))
+        .filter(|r| r.is_ok())
+        .map(|r| r.unwrap())
+        .collect();
+    assert_eq!(numbers.len(), 4);
+    Ok(())
+}
+
+fn flat_map(out: &mut dyn Write) -> io::Result<()> {
+    let mut major_cities = HashMap::new();
+    major_cities.insert("Japan", vec!["Tokyo", "Kyoto"]);
+    major_cities.insert("The United States", vec!["Portland", "Nashville"]);
+    major_cities.insert("Brazil", vec!["Sao Paulo", "Brasilia"]);
+    major_cities.insert("Kenya", vec!["Nairobi", "Mombasa"]);
+    major_cities.insert("The Netherlands", vec!["Amsterdam", "Utrecht"]);
+
+    let countries = ["Japan", "Brazil", "Kenya"];
+
+    for &city in countries.iter().flat_map(|country| &major_cities[country]) {
+        writeln!(out, "{}", city)?;
+    }
+    assert_eq!(countries.iter().flat_map(|country| &major_cities[country]).count(), 6);
+    Ok(())
+}
+
+fn scan(out: &mut dyn Write) -> io::Result<()> {
+    let iter = (0..10)
+        .scan(0, |sum, item| {
+            // 累計が10を超えたところでNoneが返却されscan()アダプタは処理を中止する
+            *sum += item;
+            if *sum > 10 {
+                None
+            } else {
+                Some(item * item)
+            }
+        });
+
+    let squares = iter.collect::<Vec<i32>>();
+    assert_eq!(squares, vec![0, 1, 4, 9, 16]);
+    writeln!(out, "{:?}", squares)
+}
+
+fn take_while(out: &mut dyn Write) -> io::Result<()> {
+    let message = "To: jimb\n\
+                   From: superego <editor@oreilly.com>\n\
+                   \n\
+                   Did you get any writing done today?\n\
+                   When will you stop wasting time plotting fractals?\n";
+
+    writeln!(out, "message header:")?;
+    // take_while()は引数predicateがfalseになったらNoneを生成してイテレートを中止する
+    for header in message.lines().take_while(|l| !l.is_empty()) {
+        writeln!(out, "{}", header)?;
+    }
+
+    writeln!(out, "message body:")?;
+    // skip_while()は引数predicateがtrueのアイテムだけをスキップして繰り返す
+    for body in message.lines().skip_while(|l| !l.is_empty()) {
+        writeln!(out, "{}", body)?;
+    }
+
+    let mut lines = message.lines();
+    // by_ref()呼び出しをするとイテレータの可変参照を借用する
+    assert_eq!(lines.by_ref().take_while(|l| !l.is_empty()).count(), 2);
+    // 上では参照を渡しているだけなので、残りをもう一度利用できる
+    assert_eq!(lines.next(), Some("Did you get any writing done today?"));
+    Ok(())
+}
+
+fn peekable(out: &mut dyn Write) -> io::Result<()> {
+    use crate::number::parse_number;
+
+    let mut chars = "226153980,1766319049".chars().peekable();
+
+    assert_eq!(parse_number(&mut chars), 226153980);
+
+    // parse_number()の内部実装がカンマを取り出さないため、ここでnext()を呼んで消費する
+    assert_eq!(chars.next(), Some(','));
+
+    let n = parse_number(&mut chars);
+    assert_eq!(n, 1766319049);
+
+    // 取り出すものが無くなったらNoneが返される
+    assert_eq!(chars.next(), None);
+    writeln!(out, "{}", n)
+}
+
+fn inspect(out: &mut dyn Write) -> io::Result<()> {
+    let mut log = Vec::new();
+    let upper_case: String = "groβe".chars()
+        .inspect(|c| log.push(format!("before: {:?}", c)))
+        .flat_map(|c| c.to_uppercase())
+        .collect();
+    assert_eq!(upper_case, "GROΒE");
+
+    // inspect()はアイテムをそのまま通過させるため、デバッグ出力などに使われる
+    for line in log {
+        writeln!(out, "{}", line)?;
+    }
+    writeln!(out, "{}", upper_case)
+}
+
+fn chain(out: &mut dyn Write) -> io::Result<()> {
+    // 1つ目のイテレータから2つ目のイテレータを繋げる
+    let v: Vec<i32> = (1..4).chain(vec![20, 30, 40]).collect();
+    assert_eq!(v, [1, 2, 3, 20, 30, 40]);
+
+    // 1つ目のイテレータから2つ目のイテレータを繋げたものをrev()で逆順にする
+    let rev_v: Vec<i32> = (1..4).chain(vec![20, 30, 40]).rev().collect();
+    assert_eq!(rev_v, [40, 30, 20, 3, 2, 1]);
+    writeln!(out, "{:?}", rev_v)
+}
+
+fn enumerate(out: &mut dyn Write) -> io::Result<()> {
+    let bands = vec![10, 20, 30, 40];
+    for (i, band) in bands.into_iter().enumerate() {
+        // enumerate()は(0, 10), (1, 20), (2, 30), (3, 40)と
+        // インデックスとアイテムのペアになったイテレータを生成する
+        writeln!(out, "{}", (i * band))?; // 0, 20, 60, 120
+    }
+    Ok(())
+}
+
+fn zip(out: &mut dyn Write) -> io::Result<()> {
+    // zip()アダプタは2つのイテレータを合わせて1つのイテレータにする
+    // 閉じ合わせるどちらかのイテレータが終了した時点でzip()アダプタも終了する
+    let v: Vec<_> = (0..).zip("ABCD".chars()).collect();
+    assert_eq!(v, vec![(0, 'A'), (1, 'B'), (2, 'C'), (3, 'D')]);
+
+    // zip()の引数はイテレータそのものでなくイテレート可能なものなら何でもよい
+    use std::iter::repeat;
+    let endings = vec!["once", "twice", "chikien soup with rice"];
+    for (going, ending) in repeat("going").zip(endings) {
+        writeln!(out, "{} {}", going, ending)?;
+    }
+    Ok(())
+}
+
+#[allow(clippy::iter_next_slice)]
+fn cloned(out: &mut dyn Write) -> io::Result<()> {
+    // cloned()はCloneを実装する型のイテレータから値をクローンして生成するイテレータを返す
+    let a = ['1', '2', '3', '∞'];
+
+    assert_eq!(a.iter().next(),          Some(&'1'));
+    assert_eq!(a.iter().cloned().next(), Some('1'));
+    writeln!(out, "{}", a.iter().cloned().collect::<String>())
+}
+
+fn cycle(out: &mut dyn Write) -> io::Result<()> {
+    let dirs = ["North", "East", "South", "West"];
+
+    // cycleアダプタは、元となるイテレータが生成するアイテムを無限に繰り返すイテレータを返す
+    // 呼ばれるイテレータのアイテムはCloneを実装する型でなければならない
+    let spin: Vec<&&str> = dirs.iter().cycle().take(6).collect();
+    assert_eq!(spin, [&"North", &"East", &"South", &"West", &"North", &"East"]);
+    writeln!(out, "{:?}", spin)
+}
+
+fn fizz_buzz(out: &mut dyn Write) -> io::Result<()> {
+    use crate::fizzbuzz;
+
+    for line in fizzbuzz::fizz_buzz().take(99) {
+        writeln!(out, "{}", line)?;
+    }
+    assert_eq!(fizzbuzz::fizz_buzz().nth(14), Some("fizzbuzz".to_string()));
+    Ok(())
+}
+
+fn fizz_buzz_rules(out: &mut dyn Write) -> io::Result<()> {
+    use crate::fizzbuzz::{self, FizzBuzz};
+
+    // 規則を登録する順に語がつながる
+    assert!(FizzBuzz::standard().iter().take(100).eq(fizzbuzz::fizz_buzz().take(100)));
+    let rules = FizzBuzz::standard()
+        .rule(7, "bazz")
+        .predicate("!", |n| n.to_string().contains('1'));
+    assert_eq!(rules.iter().nth(104), Some("fizzbuzzbazz!".to_string()));
+    rules.write_to(out, 21)
+}
+
+fn schedule(out: &mut dyn Write) -> io::Result<()> {
+    use crate::schedule::{Periodic, Schedule};
+
+    // fizzesとbuzzesのサイクルと同じく、3tickごと、5tickごとに発火する
+    let schedule = Schedule::new()
+        .with(Periodic::every(3, "fizz").offset(3))
+        .with(Periodic::every(5, "buzz").offset(5))
+        .with(Periodic::every(7, "backup").offset(2).times(3));
+    assert_eq!(schedule.clone().nth(15).unwrap().events, ["fizz", "buzz"]);
+    assert_eq!(schedule.clone().nth(16).unwrap().events, ["backup"]);
+
+    // fired()は何も発火しないtickを飛ばす
+    for tick in schedule.fired().take_while(|t| t.tick <= 30) {
+        writeln!(out, "{:>2}: {}", tick.tick, tick.events.join(", "))?;
+    }
+    Ok(())
+}
+
+fn sum_product(out: &mut dyn Write) -> io::Result<()> {
+    use crate::arith::{factorial, triangle};
+
+    assert_eq!(triangle(20), 210);
+    assert_eq!(factorial(20), 2432902008176640000);
+    writeln!(out, "triangle(20) = {}, factorial(20) = {}", triangle(20), factorial(20))
+}
+
+fn overflow(out: &mut dyn Write) -> io::Result<()> {
+    use crate::overflow::OverflowExt;
+
+    // factorial(20)はu64に収まるが、factorial(21)はオーバーフローする
+    assert_eq!((1..=20u64).checked_product(), Some(2432902008176640000));
+    assert_eq!((1..=21u64).checked_product(), None);
+    assert_eq!((1..=21u64).saturating_product(), u64::MAX);
+
+    // u128に広げて足せば、u64の最大値をいくつ足してもオーバーフローしない
+    let big = [u64::MAX, u64::MAX, u64::MAX];
+    assert_eq!(big.iter().checked_sum(), None);
+    assert_eq!(big.iter().widening_sum(), Some(3 * u64::MAX as u128));
+    writeln!(out, "21! = {:?}, wrapped = {}", (1..=21u64).checked_product(), (1..=21u64).wrapping_product())
+}
+
+fn bigint(out: &mut dyn Write) -> io::Result<()> {
+    use crate::arith::{big_factorial, factorial};
+    use crate::bigint::BigUint;
+
+    // factorial()と同じ書き方で、u64に収まらない値も求められる
+    let product: BigUint = (1..=20u64).map(BigUint::from).product();
+    assert_eq!(product, BigUint::from(factorial(20)));
+    assert_eq!(big_factorial(25).to_string(), "15511210043330985984000000");
+    writeln!(out, "100! = {}", big_factorial(100))
+}
+
+fn max_min(out: &mut dyn Write) -> io::Result<()> {
+    use std::cmp::Ordering;
+
+    // イテレータが生成するアイテムの最大と最小を返す
+    assert_eq!([-2, 0, 1, 0, -2, -5].iter().max(), Some(&1));
+    assert_eq!([-2, 0, 1, 0, -2, -5].iter().min(), Some(&-5));
+
+    // max_by()/min_by()に渡す比較用関数
+    // NaNが与えられるとpanicが起きるコードになっている
+    fn cmp(lhs: &&f64, rhs: &&f64) -> Ordering {
+        lhs.partial_cmp(rhs).unwrap()
+    }
+
+    let numbers = [1.0, 4.0, 2.0];
+    assert_eq!(numbers.iter().max_by(cmp), Some(&4.0));
+    assert_eq!(numbers.iter().min_by(cmp), Some(&1.0));
+    writeln!(out, "max = {:?}, min = {:?}", numbers.iter().max_by(cmp), numbers.iter().min_by(cmp))
+}
+
+fn float_ord(out: &mut dyn Write) -> io::Result<()> {
+    use crate::float_ord::{self, FloatOrdExt, NanError, NanPolicy, TotalF64};
+
+    // total_cmp()ならNaNが含まれていてもpanicせずに比較できる
+    let numbers = [1.0, f64::NAN, 4.0, 2.0];
+    assert!(numbers.iter().max_by(|a, b| float_ord::total_cmp(a, b)).unwrap().is_nan());
+    assert_eq!(numbers.iter().map(|&n| TotalF64(n)).min(), Some(TotalF64(1.0)));
+
+    // NaNの扱いを選んで最大と最小を求める
+    assert_eq!(numbers.iter().max_by_float(NanPolicy::Skip), Ok(Some(4.0)));
+    assert!(numbers.iter().min_by_float(NanPolicy::Propagate).unwrap().unwrap().is_nan());
+    assert_eq!(numbers.iter().max_by_float(NanPolicy::Error), Err(NanError { index: 1 }));
+
+    let mut sorted = numbers;
+    float_ord::sort_floats(&mut sorted);
+    writeln!(out, "sorted = {:?}", sorted)
+}
+
+fn stats(out: &mut dyn Write) -> io::Result<()> {
+    use crate::stats::Stats;
+
+    // sum()やmax()を別々に呼ぶ代わりに、1回の走査でまとめて集計する
+    let stats: Stats = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0].iter().collect();
+    assert_eq!(stats.count(), 8);
+    assert_eq!(stats.mean(), Some(5.0));
+    assert!((stats.std_dev().unwrap() - 2.0).abs() < 1e-12);
+    assert_eq!((stats.min(), stats.max()), (Some(2.0), Some(9.0)));
+
+    // 空のイテレータから集計した場合は全てNone
+    let empty: Stats = std::iter::empty::<f64>().collect();
+    assert_eq!(empty.mean(), None);
+    writeln!(out, "mean = {:?}, std_dev = {:?}, skewness = {:?}", stats.mean(), stats.std_dev(), stats.skewness())
+}
+
+fn quantiles(out: &mut dyn Write) -> io::Result<()> {
+    use crate::histogram::Histogram;
+    use crate::quantile::QuantileSketch;
+
+    // 全てを並べ替えなくても、中央値やp99をおおよそ求められる
+    let latencies = (1..=100_000).map(|i| ((i * 7919) % 100_000) as f64 / 100.0);
+    let sketch: QuantileSketch = latencies.clone().collect();
+    let p99 = sketch.quantile(0.99).unwrap();
+    // 順位の誤差は±0.001 * 100_000個で、隣り合う値の差は0.01なので、値の誤差は1.0以内
+    assert!((p99 - 990.0).abs() <= 1.0);
+    assert!(sketch.summary_len() < 10_000);
+
+    // 対数の区間に振り分けると、桁ごとの分布が分かる
+    let histogram: Histogram = latencies.collect();
+    for bucket in histogram.buckets().filter(|b| b.low >= 64.0) {
+        writeln!(out, "[{}, {}) {}", bucket.low, bucket.high, bucket.count)?;
+    }
+    writeln!(out, "median = {:?}, p99 = {}", sketch.median(), p99)
+}
+
+fn populations() -> HashMap<&'static str, i32> {
+    let mut populations = HashMap::new();
+    populations.insert("Portland",  583_776);
+    populations.insert("Fossil",        449);
+    populations.insert("Greenhorn",       2);
+    populations.insert("Bording",     7_762);
+    populations.insert("The Dalles", 15_340);
+    populations
+}
+
+fn max_by_key(out: &mut dyn Write) -> io::Result<()> {
+    let populations = populations();
+
+    // max_by_key()/min_by_key()では各アイテムに引数クロージャを適用した結果のうち
+    // 最大/最小のものを返す
+    let max = populations.iter().max_by_key(|&(_name, pop)|pop);
+    let min = populations.iter().min_by_key(|&(_name, pop)|pop);
+    assert_eq!(max, Some((&"Portland", &583_776)));
+    assert_eq!(min, Some((&"Greenhorn", &2)));
+    writeln!(out, "max = {:?}, min = {:?}", max, min)
+}
+
+fn top_k(out: &mut dyn Write) -> io::Result<()> {
+    use crate::select::SelectExt;
+
+    let populations = populations();
+
+    // max_by_key()は1つしか返さないが、top_k_by_key()は上位k個を大きい順に返す
+    let largest = populations.iter().top_k_by_key(3, |&(_name, pop)| *pop);
+    assert_eq!(largest, vec![(&"Portland", &583_776), (&"The Dalles", &15_340), (&"Bording", &7_762)]);
+    assert_eq!(populations.values().cloned().bottom_k(2), vec![2, 449]);
+    assert_eq!(populations.values().kth_smallest(2), Some(&7_762));
+    writeln!(out, "{:?}", largest)
+}
+
+fn compare(out: &mut dyn Write) -> io::Result<()> {
+    let packed =  "Helen of Troy";
+    let spaced =  "Helen   of   Troy";
+    let obscure = "Helen of Sandusky";
+
+    assert!(packed != spaced);
+    // 空白区切りの単語イテレータ同士で比較すると真になる
+    assert!(packed.split_whitespace().eq(spaced.split_whitespace()));
+
+    assert!(spaced < obscure);
+    // 'Troy' > 'Sandusky' の比較結果となるため真になる
+    assert!(spaced.split_whitespace().gt(obscure.split_whitespace()));
+    writeln!(out, "{:?} == {:?} word by word", packed, spaced)
+}
+
+fn any_all(out: &mut dyn Write) -> io::Result<()> {
+    let id = "Iterator";
+
+    // chars()で取り出したアイテムのどれかがis_uppercase()にtrueを返したら繰り返しを中止
+    assert!( id.chars().any(char::is_uppercase));
+    // chars()で取り出したアイテムのすべてがis_uppercase()にtrueを返す時だけtrueとなる
+    assert!(!id.chars().all(char::is_uppercase));
+    writeln!(out, "{:?} has an uppercase letter but is not all uppercase", id)
+}
+
+fn position(out: &mut dyn Write) -> io::Result<()> {
+    let text = "Xerxes";
+    assert_eq!(text.chars().position(|c| c == 'e'), Some(1));
+    assert_eq!(text.chars().position(|c| c == 'z'), None);
+
+    // rposition()はサイズが決定しているイテレータでなければ使えない
+    let bytes = b"Xerxes";
+    assert_eq!(bytes.iter().rposition(|&c| c == b'e'), Some(4));
+    writeln!(out, "first 'e' at 1, last 'e' at 4")
+}
+
+#[allow(clippy::legacy_numeric_constants, clippy::unnecessary_fold)]
+fn fold(out: &mut dyn Write) -> io::Result<()> {
+    // fn fold<A, F>(self, init: A, f: F) -> A
+    let a = [5, 6, 7, 8, 9, 10];
+
+    assert_eq!(a.iter().fold(0, |n, _| n+1), 6);      // like count()
+    assert_eq!(a.iter().fold(0, |n, i| n+i), 45);     // like sum()
+    assert_eq!(a.iter().fold(1, |n, i| n*i), 151200); // like product()
+
+    // like max()
+    assert_eq!(a.iter().fold(i32::min_value(), |m, &i| std::cmp::max(m, i)),
+              10);
+
+    let a = ["Pack ", "my ", "box ", "with ",
+             "five ", "dozen ", "liquor ", "jugs"];
+    let pangram = a.iter().fold(String::new(),
+                               |mut s, &w| { s.push_str(w); s});
+    assert_eq!(pangram, "Pack my box with five dozen liquor jugs");
+    writeln!(out, "{}", pangram)
+}
+
+#[allow(clippy::double_ended_iterator_last, clippy::iter_nth_zero)]
+fn nth_last(out: &mut dyn Write) -> io::Result<()> {
+    let mut squares = (0..10).map(|i| i*i);
+
+    // nth()はその数だけアイテムをスキップし、その次のアイテムを返す
+    assert_eq!(squares.nth(4), Some(16));
+    assert_eq!(squares.nth(0), Some(25));
+    assert_eq!(squares.nth(6), None);
+
+    // last()メソッドは対象のアイテムを先頭から最後まで消費する
+    let squares = (0..10).map(|i| i*i);
+    assert_eq!(squares.last(), Some(81));
+    writeln!(out, "last square = 81")
+}
+
+fn find_population(out: &mut dyn Write) -> io::Result<()> {
+    let populations = populations();
+
+    // find()はイテレータからアイテムを引き出し、引数のクロージャが最初にtrueとなったアイテムを返す
+    assert_eq!(populations.iter().find(|&(_name, &pop)| pop > 1_000_999), None);
+    let found = populations.iter().find(|&(_name, &pop)| pop > 500_999);
+    assert_eq!(found, Some((&"Portland", &583_776)));
+    writeln!(out, "{:?}", found)
+}
+
+fn extend(out: &mut dyn Write) -> io::Result<()> {
+    // 標準のコレクション（Stringを含む）は全てstd::iter::Extendを実装しているので拡張できる
+    let mut v: Vec<i32> = (0..5).map(|i| 1 << i).collect();
+    v.extend(&[31, 57, 99, 163]);
+    assert_eq!(v, &[1, 2, 4, 8, 16, 31, 57, 99, 163]);
+    writeln!(out, "{:?}", v)
+}
+
+fn partition(out: &mut dyn Write) -> io::Result<()> {
+    let things = ["doorknob", "mushroom", "noodle", "giraffe", "grapefruit"];
+
+    // partition()は引数のクロージャでコレクションを分割する
+    let (living, nonliving): (Vec<&str>, Vec<&str>) = things.iter().partition(|name| name.as_bytes()[0] & 1 != 0);
+
+    assert_eq!(living,    vec!["mushroom", "giraffe", "grapefruit"]);
+    assert_eq!(nonliving, vec!["doorknob", "noodle"]);
+    writeln!(out, "living: {:?}, nonliving: {:?}", living, nonliving)
+}
+
+fn parse_all(out: &mut dyn Write) -> io::Result<()> {
+    use crate::parse_all::ParseAllExt;
+
+    let text = "1\nfrond .25 289\n3.1415 estuary\n";
+
+    // ok()で捨てていたエラーを、何番目のどのトークンだったかと一緒に残す
+    let result = text.split_whitespace()
+        .parse_partition(1, |w| f64::from_str(w));
+    assert_eq!(result.parsed.len(), 4);
+    assert_eq!(result.rejected_count, 2);
+    writeln!(out, "{}", result.rejected[0])
+}
+
+fn float_parse(out: &mut dyn Write) -> io::Result<()> {
+    use crate::float_parse::parse_float;
+
+    // parse_number()と同じくPeekableから数値を読み取り、区切り文字は消費せずに残す
+    let mut chars = "-12.5e3,.25".chars().peekable();
+    assert_eq!(parse_float(&mut chars), Ok(-12500.0));
+    assert_eq!(chars.next(), Some(','));
+    assert_eq!(parse_float(&mut chars), Ok(0.25));
+
+    // 不正な入力は何文字目で失敗したかを返す
+    let err = parse_float(&mut "1.5e+x".chars().peekable()).unwrap_err();
+    assert_eq!(err.position, 5);
+    writeln!(out, "{}", err)
+}
+
+fn lexer(out: &mut dyn Write) -> io::Result<()> {
+    use crate::lexer::{Lexer, TokenKind};
+
+    let source = "let ratio = 2.5; // circle\nname = \"Jaeger\\n\" ` 42";
+
+    // Lexerはトークン列を生成するイテレータなので、アダプタをそのまま繋げられる
+    let idents = Lexer::new(source.chars())
+        .filter(|token| matches!(token.kind, TokenKind::Ident(_)))
+        .count();
+    assert_eq!(idents, 3);
+
+    // 各トークンは行と列の位置を持つ
+    for token in Lexer::new(source.chars()).filter(|t| t.span.start.line == 2) {
+        writeln!(out, "{}:{} {:?}", token.span.start.line, token.span.start.column, token.kind)?;
+    }
+    Ok(())
+}
+
+fn multipeek(out: &mut dyn Write) -> io::Result<()> {
+    use crate::multipeek::MultiPeekExt;
+
+    // Peekableでは1文字先しか見えないため"..="と".."を区別できないが、MultiPeekなら何文字先でも覗ける
+    let mut chars = "1..=10".chars().multipeek();
+    assert_eq!(chars.next_if(|c| c.is_ascii_digit()), Some('1'));
+    assert_eq!(chars.peek_nth(2), Some(&'='));
+    let ahead: String = chars.peek_while(|c| !c.is_ascii_digit()).collect();
+    assert_eq!(ahead, "..=");
+    writeln!(out, "peeked {:?} without consuming: {:?}", ahead, chars.collect::<String>())
+}
+
+fn combinator(out: &mut dyn Write) -> io::Result<()> {
+    use crate::combinator::{Input, Parser, eof, left, many1, map, satisfy, sep_by, token};
+
+    // parse_number()のループを、小さなパーサを組み合わせて書き直したもの
+    let digit = satisfy("digit", |c: &char| c.is_ascii_digit());
+    let number = map(many1(digit), |digits: Vec<char>| {
+        digits.iter().fold(0, |n, d| n * 10 + d.to_digit(10).unwrap())
+    });
+    let numbers = left(sep_by(number, token(',')), eof());
+
+    let mut input = Input::new("226153980,1766319049".chars());
+    assert_eq!(numbers.parse(&mut input), Ok(vec![226153980, 1766319049]));
+
+    // 失敗した時は最も先まで進んだ位置と、そこで期待されていたものを報告する
+    let err = numbers.parse(&mut Input::new("226153980,17x".chars())).unwrap_err();
+    assert_eq!(err.position, 12);
+    writeln!(out, "{}", err)
+}
+
+fn message(out: &mut dyn Write) -> io::Result<()> {
+    use crate::message::parse_message;
+
+    // 折り返されたヘッダ（空白で始まる継続行）と単独の'\r'の行末を含むメッセージを読む
+    let message = "To: jimb\r\n\
+                   From: superego\r\n \
+                   <editor@oreilly.com>\r\
+                   subject: Fractals\n\
+                   \r\n\
+                   Did you get any writing done today?\r\n";
+
+    let mut parsed = parse_message(message).unwrap();
+    assert_eq!(parsed.get("from"), Some("superego <editor@oreilly.com>"));
+    for header in parsed.headers() {
+        writeln!(out, "{}: {}", header.name, header.value)?;
+    }
+    assert_eq!(parsed.body().next(), Some("Did you get any writing done today?"));
+    Ok(())
+}
+
+fn mime(out: &mut dyn Write) -> io::Result<()> {
+    use crate::mime::Part;
+
+    let message = "Content-Type: multipart/mixed; boundary=\"outer\"\r\n\
+                   \r\n\
+                   --outer\r\n\
+                   Content-Type: text/plain\r\n\
+                   Content-Transfer-Encoding: quoted-printable\r\n\
+                   \r\n\
+                   gro=C3=9Fe\r\n\
+                   --outer\r\n\
+                   Content-Transfer-Encoding: base64\r\n\
+                   \r\n\
+                   ZnJhY3RhbHM=\r\n\
+                   --outer--\r\n";
+
+    let root = Part::parse(message).unwrap();
+
+    // 転送エンコーディングはdecoded_body()のイテレータで必要な分だけデコードされる
+    let bodies: Vec<String> = root.multipart().unwrap()
+        .map(|part| String::from_utf8(part.unwrap().decoded_body().collect()).unwrap())
+        .collect();
+    assert_eq!(bodies, ["große", "fractals"]);
+    writeln!(out, "{:?}", bodies)
+}
+
+fn mbox(out: &mut dyn Write) -> io::Result<()> {
+    use crate::mbox::Mbox;
+
+    let mailbox = "From superego Mon Jan  1 00:00:00 2018\n\
+                   Subject: writing\n\
+                   \n\
+                   >From the desk of your superego.\n\
+                   \n\
+                   From jimb Mon Jan  1 09:00:00 2018\n\
+                   Subject: Re: writing\n\
+                   \n\
+                   Fractals.\n";
+
+    // BufReadから1通ずつメッセージを取り出す
+    for message in Mbox::new(mailbox.as_bytes()) {
+        let message = message?;
+        let subject = message.part().unwrap().header("Subject").unwrap().to_string();
+        writeln!(out, "{}: {}", message.envelope(), subject)?;
+    }
+    assert_eq!(Mbox::new(mailbox.as_bytes()).count(), 2);
+    Ok(())
+}
+
+fn json(out: &mut dyn Write) -> io::Result<()> {
+    use crate::json::{self, Event};
+
+    let text = r#"{"city": "Portland", "neighbors": [{"city": "Fossil"}], "big": true}"#;
+
+    // DOMを作らず、イベントのイテレータとして読む
+    let keys: Vec<String> = json::from_str(text)
+        .filter_map(|event| match event {
+            Ok(Event::Key(key)) => Some(key),
+            _ => None
+        })
+        .collect();
+    assert_eq!(keys, ["city", "neighbors", "city", "big"]);
+
+    // エラーは行と列の位置を持つ
+    let err = json::from_reader("[1, 2,\n 3,]".as_bytes())
+        .find_map(Result::err)
+        .unwrap();
+    assert_eq!((err.position.line, err.position.column), (2, 4));
+    writeln!(out, "{}", err)
+}
+
+fn csv(out: &mut dyn Write) -> io::Result<()> {
+    use crate::csv::Reader;
+
+    // 引用符の中の区切り文字や改行も正しく読める
+    let text = "city,population\n\
+                \"The Dalles\",15340\n\
+                Greenhorn,two\n";
+    let records: Vec<_> = Reader::new(text.as_bytes()).has_headers(true)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(records[0].parse_by_name::<u32>("population").unwrap(), 15340);
+
+    // 型変換に失敗すると、そのレコードの行番号とフィールドの番号を報告する
+    let err = records[1].parse_by_name::<u32>("population").unwrap_err();
+    assert_eq!((err.row, err.column), (3, 2));
+    writeln!(out, "{}", err)
+}
+
+fn contract(out: &mut dyn Write) -> io::Result<()> {
+    use crate::contract::{self, Law};
+    use crate::flakey::Flakey;
+
+    // 自作イテレータがIteratorの約束を守っているかを検査できる
+    let violation = contract::check_iterator(|| Flakey(true)).unwrap_err();
+    assert_eq!(violation.law, Law::Fused);
+    assert!(contract::check_iterator(|| Flakey(true).fuse()).is_ok());
+    writeln!(out, "{}", violation)
+}
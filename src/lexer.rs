@@ -0,0 +1,279 @@
+use crate::multipeek::MultiPeek;
+
+// 入力中の位置（offsetは文字単位、lineとcolumnは1始まり）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize
+}
+
+// トークンが占める範囲（startを含みendを含まない）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    Ident(String),
+    Int(u64),
+    Float(f64),
+    Str(String),
+    Punct(char),
+    Comment(String),
+    // 不正な入力。字句解析はここで止まらず、次のトークンから再開する
+    Error(String)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span
+}
+
+const PUNCTUATION: &str = "+-*/%=<>!&|^~.,;:?@#$()[]{}";
+
+// MultiPeekで数文字先まで覗きながらトークンを切り出すイテレータ
+// 空白は読み飛ばし、不正な文字はTokenKind::Errorとして返してから解析を続ける
+#[derive(Clone)]
+pub struct Lexer<I>
+    where I: Iterator<Item=char>
+{
+    chars: MultiPeek<I>,
+    position: Position
+}
+
+impl<I> Lexer<I>
+    where I: Iterator<Item=char>
+{
+    pub fn new<T>(input: T) -> Lexer<I>
+        where T: IntoIterator<Item=char, IntoIter=I>
+    {
+        Lexer {
+            chars: MultiPeek::new(input.into_iter()),
+            position: Position { offset: 0, line: 1, column: 1 }
+        }
+    }
+
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().cloned()
+    }
+
+    fn peek_nth(&mut self, n: usize) -> Option<char> {
+        self.chars.peek_nth(n).cloned()
+    }
+
+    // 1文字消費して位置を進める
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        self.position.offset += 1;
+        if c == '\n' {
+            self.position.line += 1;
+            self.position.column = 1;
+        } else {
+            self.position.column += 1;
+        }
+        Some(c)
+    }
+
+    fn bump_while<P>(&mut self, text: &mut String, predicate: P)
+        where P: Fn(char) -> bool
+    {
+        while let Some(c) = self.peek() {
+            if !predicate(c) {
+                break;
+            }
+            text.push(c);
+            self.bump();
+        }
+    }
+
+    fn ident(&mut self) -> TokenKind {
+        let mut text = String::new();
+        self.bump_while(&mut text, |c| c == '_' || c.is_alphanumeric());
+        TokenKind::Ident(text)
+    }
+
+    fn number(&mut self) -> TokenKind {
+        let mut text = String::new();
+        let mut is_float = false;
+
+        // "0x"か"0X"で始まるものは16進数の整数
+        if self.peek() == Some('0') && matches!(self.peek_nth(1), Some('x') | Some('X')) {
+            self.bump();
+            let x = self.bump().unwrap();
+            let mut hex = String::new();
+            self.bump_while(&mut hex, |c| c.is_ascii_hexdigit());
+            return match u64::from_str_radix(&hex, 16) {
+                Ok(n) if !self.peek().is_some_and(|c| c == '_' || c.is_alphanumeric()) => TokenKind::Int(n),
+                _ => {
+                    self.bump_while(&mut hex, |c| c == '_' || c.is_alphanumeric());
+                    TokenKind::Error(format!("invalid hexadecimal number \"0{}{}\"", x, hex))
+                }
+            };
+        }
+
+        self.bump_while(&mut text, |c| c.is_ascii_digit());
+        // "1..2"のような範囲を数値として読まないよう、'.'の次が数字の時だけ小数部とみなす
+        if self.peek() == Some('.') && self.peek_nth(1).is_some_and(|c| c.is_ascii_digit()) {
+            is_float = true;
+            text.push('.');
+            self.bump();
+            self.bump_while(&mut text, |c| c.is_ascii_digit());
+        }
+        if let Some(e) = self.peek().filter(|&c| c == 'e' || c == 'E') {
+            is_float = true;
+            text.push(e);
+            self.bump();
+            if let Some(sign) = self.peek().filter(|&c| c == '+' || c == '-') {
+                text.push(sign);
+                self.bump();
+            }
+            let digits = text.len();
+            self.bump_while(&mut text, |c| c.is_ascii_digit());
+            if text.len() == digits {
+                // "1else"のように英字が続く場合は、数値の後に識別子を残さずまとめて1つのエラーにする
+                if self.peek().is_some_and(|c| c == '_' || c.is_alphanumeric()) {
+                    self.bump_while(&mut text, |c| c == '_' || c.is_alphanumeric());
+                    return TokenKind::Error(format!("invalid number {:?}", text));
+                }
+                return TokenKind::Error(format!("missing exponent digits in {:?}", text));
+            }
+        }
+        // 数字の直後に英字が続くもの（"12ab"など）はまとめて1つのエラーにする
+        if self.peek().is_some_and(|c| c == '_' || c.is_alphabetic()) {
+            self.bump_while(&mut text, |c| c == '_' || c.is_alphanumeric());
+            return TokenKind::Error(format!("invalid number {:?}", text));
+        }
+
+        if is_float {
+            match text.parse() {
+                Ok(f) => TokenKind::Float(f),
+                Err(_) => TokenKind::Error(format!("invalid float {:?}", text))
+            }
+        } else {
+            match text.parse() {
+                Ok(n) => TokenKind::Int(n),
+                Err(_) => TokenKind::Error(format!("integer {} is too large", text))
+            }
+        }
+    }
+
+    // 開きの'"'を読んだ後に呼ばれる
+    fn string(&mut self) -> TokenKind {
+        let mut value = String::new();
+        let mut error = None;
+        loop {
+            let c = match self.bump() {
+                Some(c) => c,
+                None => return TokenKind::Error("unterminated string literal".to_string())
+            };
+            match c {
+                '"' => break,
+                '\\' => match self.bump() {
+                    Some('n') => value.push('\n'),
+                    Some('r') => value.push('\r'),
+                    Some('t') => value.push('\t'),
+                    Some('0') => value.push('\0'),
+                    Some('\\') => value.push('\\'),
+                    Some('"') => value.push('"'),
+                    Some('\'') => value.push('\''),
+                    Some('u') => match self.unicode_escape() {
+                        Ok(c) => value.push(c),
+                        Err(e) => { error.get_or_insert(e); }
+                    },
+                    Some(other) => {
+                        error.get_or_insert(format!("unknown escape sequence \\{}", other));
+                    }
+                    None => return TokenKind::Error("unterminated string literal".to_string())
+                },
+                _ => value.push(c)
+            }
+        }
+        // 不正なエスケープがあっても閉じの'"'までは読み進めてから報告する
+        match error {
+            Some(e) => TokenKind::Error(e),
+            None => TokenKind::Str(value)
+        }
+    }
+
+    // "\u{1F600}"形式のエスケープ
+    fn unicode_escape(&mut self) -> Result<char, String> {
+        if self.peek() != Some('{') {
+            return Err("expected '{' after \\u".to_string());
+        }
+        self.bump();
+        let mut hex = String::new();
+        self.bump_while(&mut hex, |c| c.is_ascii_hexdigit());
+        if self.peek() != Some('}') {
+            return Err("unterminated unicode escape".to_string());
+        }
+        self.bump();
+        u32::from_str_radix(&hex, 16).ok()
+            .and_then(std::char::from_u32)
+            .ok_or_else(|| format!("invalid unicode escape \\u{{{}}}", hex))
+    }
+
+    // 最初の'/'を読んだ後に呼ばれる。コメントでなければ'/'そのものを返す
+    fn slash(&mut self) -> TokenKind {
+        match self.peek() {
+            Some('/') => {
+                self.bump();
+                let mut text = String::new();
+                self.bump_while(&mut text, |c| c != '\n');
+                TokenKind::Comment(text)
+            }
+            Some('*') => {
+                self.bump();
+                let mut text = String::new();
+                loop {
+                    match self.bump() {
+                        Some('*') if self.peek() == Some('/') => {
+                            self.bump();
+                            return TokenKind::Comment(text);
+                        }
+                        Some(c) => text.push(c),
+                        None => return TokenKind::Error("unterminated block comment".to_string())
+                    }
+                }
+            }
+            _ => TokenKind::Punct('/')
+        }
+    }
+}
+
+impl<I> Iterator for Lexer<I>
+    where I: Iterator<Item=char>
+{
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+
+        let start = self.position;
+        let c = self.peek()?;
+        let kind = if c == '_' || c.is_alphabetic() {
+            self.ident()
+        } else if c.is_ascii_digit() {
+            self.number()
+        } else {
+            self.bump();
+            match c {
+                '"' => self.string(),
+                '/' => self.slash(),
+                c if PUNCTUATION.contains(c) => TokenKind::Punct(c),
+                c => TokenKind::Error(format!("unexpected character {:?}", c))
+            }
+        };
+
+        Some(Token { kind, span: Span { start, end: self.position } })
+    }
+}
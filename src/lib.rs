@@ -0,0 +1,31 @@
+pub mod float_parse;
+pub mod lexer;
+pub mod multipeek;
+pub mod combinator;
+pub mod json;
+pub mod csv;
+pub mod parse_all;
+pub mod message;
+pub mod mime;
+pub mod mbox;
+pub mod contract;
+pub mod range;
+pub mod tree;
+pub mod number;
+pub mod fizzbuzz;
+pub mod schedule;
+pub mod property;
+pub mod flakey;
+pub mod arith;
+pub mod overflow;
+pub mod bigint;
+pub mod sequences;
+pub mod series;
+pub mod float_ord;
+pub mod stats;
+pub mod quantile;
+pub mod histogram;
+pub mod select;
+pub mod dump;
+pub mod lessons;
+pub mod repl;
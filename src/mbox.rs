@@ -0,0 +1,114 @@
+use std::io::{self, BufRead};
+use std::iter::Peekable;
+
+use crate::message::{self, HeaderError, Lines, Message};
+use crate::mime::Part;
+
+// mboxから取り出した1通分のメッセージ
+#[derive(Debug, Clone, PartialEq)]
+pub struct MboxMessage {
+    envelope: String,
+    text: String
+}
+
+impl MboxMessage {
+    // "From "行の残り（送信者と日時）
+    pub fn envelope(&self) -> &str {
+        &self.envelope
+    }
+
+    // ">From "の引用を戻した、ヘッダと本文からなるメッセージ本体
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    // ヘッダの一覧と本文の行イテレータに分ける
+    pub fn message(&self) -> Result<Message<'_, Lines<'_>>, HeaderError<'_>> {
+        message::parse_message(&self.text)
+    }
+
+    // ヘッダと本文のスライスに分ける。multipartならPart::multipart()でさらに分けられる
+    pub fn part(&self) -> Result<Part<'_>, HeaderError<'_>> {
+        Part::parse(&self.text)
+    }
+}
+
+fn is_separator(line: &io::Result<String>) -> bool {
+    match *line {
+        Ok(ref line) => line.starts_with("From "),
+        Err(_) => false
+    }
+}
+
+// mboxrd形式の">From "、">>From "...から'>'を1つ取り除く
+fn unquote(line: &str) -> &str {
+    let quoted = line.trim_start_matches('>');
+    if quoted.len() < line.len() && quoted.starts_with("From ") {
+        &line[1..]
+    } else {
+        line
+    }
+}
+
+// "From "で始まる行ごとにメッセージを区切り、1通ずつ返すイテレータ
+// 行のイテレータをメッセージをまたいで使い回すので、メールボックス全体をメモリに読み込むことはない
+pub struct Mbox<I>
+    where I: Iterator<Item=io::Result<String>>
+{
+    lines: Peekable<I>
+}
+
+impl<R> Mbox<io::Lines<R>>
+    where R: BufRead
+{
+    pub fn new(reader: R) -> Mbox<io::Lines<R>> {
+        Mbox::from_lines(reader.lines())
+    }
+}
+
+impl<I> Mbox<I>
+    where I: Iterator<Item=io::Result<String>>
+{
+    pub fn from_lines(lines: I) -> Mbox<I> {
+        Mbox { lines: lines.peekable() }
+    }
+}
+
+impl<I> Iterator for Mbox<I>
+    where I: Iterator<Item=io::Result<String>>
+{
+    type Item = io::Result<MboxMessage>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // 最初の"From "行より前にあるものは読み飛ばす
+        let envelope = loop {
+            match self.lines.next()? {
+                Ok(line) => {
+                    if let Some(rest) = line.strip_prefix("From ") {
+                        break rest.to_string();
+                    }
+                }
+                Err(e) => return Some(Err(e))
+            }
+        };
+
+        // 次の"From "行の手前までを、そのメッセージの行として読む
+        let mut text = String::new();
+        while let Some(line) = self.lines.next_if(|line| !is_separator(line)) {
+            match line {
+                Ok(line) => {
+                    text.push_str(unquote(&line));
+                    text.push('\n');
+                }
+                Err(e) => return Some(Err(e))
+            }
+        }
+
+        // 区切りの"From "行の前に置かれる空行はメッセージに含めない
+        if text.ends_with("\n\n") {
+            text.pop();
+        }
+
+        Some(Ok(MboxMessage { envelope, text }))
+    }
+}
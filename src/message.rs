@@ -0,0 +1,181 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::iter::Peekable;
+
+// str::lines()は単独の'\r'を行末とみなさないため、CRLF、LF、単独のCRのどれでも行を区切るイテレータ
+#[derive(Debug, Clone)]
+pub struct Lines<'a> {
+    rest: Option<&'a str>
+}
+
+pub fn lines(text: &str) -> Lines<'_> {
+    Lines { rest: if text.is_empty() { None } else { Some(text) } }
+}
+
+impl<'a> Lines<'a> {
+    // まだ読んでいない残りの部分
+    pub fn remainder(&self) -> &'a str {
+        self.rest.unwrap_or("")
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let text = self.rest?;
+        match text.find(&['\r', '\n'][..]) {
+            Some(i) => {
+                let next = if text[i..].starts_with("\r\n") { i + 2 } else { i + 1 };
+                self.rest = if next < text.len() { Some(&text[next..]) } else { None };
+                Some(&text[..i])
+            }
+            None => {
+                self.rest = None;
+                Some(text)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Header<'a> {
+    pub name: &'a str,
+    // 継続行を連結した値。折り返されていなければ元の行を借用したまま
+    pub value: Cow<'a, str>
+}
+
+// ヘッダとして解釈できない行。lineはヘッダ部の先頭からの行番号（1始まり）
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeaderError<'a> {
+    pub line: usize,
+    pub text: &'a str
+}
+
+impl<'a> fmt::Display for HeaderError<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "malformed header at line {}: {:?}", self.line, self.text)
+    }
+}
+
+impl<'a> std::error::Error for HeaderError<'a> {}
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+fn is_continuation(line: &str) -> bool {
+    line.starts_with(' ') || line.starts_with('\t')
+}
+
+// 行のイテレータからヘッダを1つずつ取り出す
+// 空行（空白だけの行を含む）で止まり、その空行は消費するので、
+// by_ref()と同じく元のイテレータは本文の先頭から続けて使える
+pub struct Headers<'r, 'a, I>
+    where I: Iterator<Item=&'a str>
+{
+    lines: &'r mut Peekable<I>,
+    line: usize,
+    done: bool
+}
+
+pub fn headers<'r, 'a, I>(lines: &'r mut Peekable<I>) -> Headers<'r, 'a, I>
+    where I: Iterator<Item=&'a str>
+{
+    Headers { lines, line: 0, done: false }
+}
+
+impl<'r, 'a, I> Iterator for Headers<'r, 'a, I>
+    where I: Iterator<Item=&'a str>
+{
+    type Item = Result<Header<'a>, HeaderError<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let first = match self.lines.next() {
+            Some(line) if !is_blank(line) => line,
+            _ => {
+                self.done = true;
+                return None;
+            }
+        };
+        self.line += 1;
+
+        let colon = match first.find(':') {
+            Some(i) if i > 0 && !is_continuation(first) => i,
+            _ => return Some(Err(HeaderError { line: self.line, text: first }))
+        };
+        let name = first[..colon].trim_end();
+        let mut value = Cow::Borrowed(first[colon + 1..].trim());
+
+        // 空白で始まる行は前の行の続き（RFC 5322の折り返し）なので、改行を取り除いて連結する
+        while let Some(&line) = self.lines.peek() {
+            if is_blank(line) || !is_continuation(line) {
+                break;
+            }
+            self.lines.next();
+            self.line += 1;
+            value.to_mut().push_str(line);
+        }
+        if let Cow::Owned(ref mut s) = value {
+            let len = s.trim_end().len();
+            s.truncate(len);
+        }
+
+        Some(Ok(Header { name, value }))
+    }
+}
+
+// ヘッダの一覧と、本文の行を返すイテレータを持つメッセージ
+pub struct Message<'a, I>
+    where I: Iterator<Item=&'a str>
+{
+    headers: Vec<Header<'a>>,
+    body: Peekable<I>
+}
+
+impl<'a, I> Message<'a, I>
+    where I: Iterator<Item=&'a str>
+{
+    // ヘッダ部を読み、本文は読まずにイテレータのまま保持する
+    pub fn parse<T>(lines: T) -> Result<Message<'a, I>, HeaderError<'a>>
+        where T: IntoIterator<Item=&'a str, IntoIter=I>
+    {
+        let mut body = lines.into_iter().peekable();
+        let headers = headers(&mut body).collect::<Result<Vec<_>, _>>()?;
+        Ok(Message { headers, body })
+    }
+
+    pub fn headers(&self) -> &[Header<'a>] {
+        &self.headers
+    }
+
+    // 名前の大文字小文字を区別せずに最初のヘッダの値を返す
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|h| h.name.eq_ignore_ascii_case(name))
+            .map(|h| h.value.as_ref())
+    }
+
+    // 同じ名前のヘッダ（Receivedなど）を全て返す
+    pub fn get_all<'s>(&'s self, name: &'s str) -> impl Iterator<Item=&'s str> + 's {
+        self.headers.iter()
+            .filter(move |h| h.name.eq_ignore_ascii_case(name))
+            .map(|h| h.value.as_ref())
+    }
+
+    pub fn body(&mut self) -> &mut Peekable<I> {
+        &mut self.body
+    }
+
+    pub fn into_body(self) -> Peekable<I> {
+        self.body
+    }
+}
+
+// 文字列全体からメッセージを読む
+pub fn parse_message(text: &str) -> Result<Message<'_, Lines<'_>>, HeaderError<'_>> {
+    Message::parse(lines(text))
+}
@@ -0,0 +1,349 @@
+use crate::message::{self, Header, HeaderError, Message};
+use crate::multipeek::MultiPeek;
+
+// Content-Typeの値からメディアタイプ（"multipart/mixed"など）を取り出す
+pub fn media_type(content_type: &str) -> &str {
+    content_type.split(';').next().unwrap_or("").trim()
+}
+
+// Content-Typeの値からパラメータを取り出す。名前の大文字小文字は区別せず、引用符は外す
+pub fn parameter<'a>(content_type: &'a str, name: &str) -> Option<&'a str> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let mut kv = param.splitn(2, '=');
+        let key = kv.next()?.trim();
+        let value = kv.next()?.trim();
+        if key.eq_ignore_ascii_case(name) {
+            Some(value.trim_matches('"'))
+        } else {
+            None
+        }
+    })
+}
+
+pub fn boundary(content_type: &str) -> Option<&str> {
+    parameter(content_type, "boundary")
+}
+
+// ヘッダ部と本文を区切る空行の位置で分ける。空行がなければ全体をヘッダ部とする
+pub fn split_head(text: &str) -> (&str, &str) {
+    let mut lines = message::lines(text);
+    loop {
+        let start = text.len() - lines.remainder().len();
+        match lines.next() {
+            Some(line) if line.trim().is_empty() => {
+                return (&text[..start], lines.remainder());
+            }
+            Some(_) => {}
+            None => return (text, "")
+        }
+    }
+}
+
+// ヘッダと本文を持つMIMEエンティティ（メッセージ全体またはmultipartの各パート）
+#[derive(Debug, Clone, PartialEq)]
+pub struct Part<'a> {
+    headers: Vec<Header<'a>>,
+    body: &'a str
+}
+
+impl<'a> Part<'a> {
+    pub fn parse(text: &'a str) -> Result<Part<'a>, HeaderError<'a>> {
+        let (head, body) = split_head(text);
+        let message = Message::parse(message::lines(head))?;
+        Ok(Part { headers: message.headers().to_vec(), body })
+    }
+
+    pub fn headers(&self) -> std::slice::Iter<'_, Header<'a>> {
+        self.headers.iter()
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|h| h.name.eq_ignore_ascii_case(name))
+            .map(|h| h.value.as_ref())
+    }
+
+    // Content-Typeがない時はRFC 2045の既定値text/plainとみなす
+    pub fn content_type(&self) -> &str {
+        self.header("Content-Type").unwrap_or("text/plain")
+    }
+
+    // 転送エンコーディングを解く前の本文
+    pub fn body(&self) -> &'a str {
+        self.body
+    }
+
+    // multipart/*であれば、その本文を各パートに分けるイテレータを返す
+    pub fn multipart(&self) -> Option<Multipart<'a>> {
+        let content_type = self.header("Content-Type")?;
+        if !media_type(content_type).to_ascii_lowercase().starts_with("multipart/") {
+            return None;
+        }
+        Some(Multipart::new(self.body, boundary(content_type)?))
+    }
+
+    // Content-Transfer-Encodingに従って本文を1バイトずつ遅延的にデコードする
+    pub fn decoded_body(&self) -> Decoded<'a> {
+        let encoding = self.header("Content-Transfer-Encoding").unwrap_or("7bit");
+        let bytes = self.body.bytes();
+        if encoding.eq_ignore_ascii_case("base64") {
+            Decoded::Base64(Base64Decoder::new(bytes))
+        } else if encoding.eq_ignore_ascii_case("quoted-printable") {
+            Decoded::QuotedPrintable(QuotedPrintableDecoder::new(bytes))
+        } else {
+            Decoded::Identity(bytes)
+        }
+    }
+}
+
+// multipartの本文をboundaryで区切り、各パートを順に返すイテレータ
+// 最初の区切り行より前（preamble）と終端の区切り行より後（epilogue）は無視する
+pub struct Multipart<'a> {
+    rest: &'a str,
+    boundary: String,
+    started: bool,
+    done: bool
+}
+
+enum Delimiter {
+    Part,
+    Close
+}
+
+impl<'a> Multipart<'a> {
+    pub fn new(body: &'a str, boundary: &str) -> Multipart<'a> {
+        Multipart { rest: body, boundary: boundary.to_string(), started: false, done: false }
+    }
+
+    fn delimiter(&self, line: &str) -> Option<Delimiter> {
+        let rest = line.strip_prefix("--")?.strip_prefix(self.boundary.as_str())?;
+        if rest.trim_end().is_empty() {
+            Some(Delimiter::Part)
+        } else if rest.strip_prefix("--")?.trim_end().is_empty() {
+            Some(Delimiter::Close)
+        } else {
+            None
+        }
+    }
+
+    // 次の区切り行を探し、(その前の行末までの内容, 区切りの種類)を返す
+    // 区切り行の直前の改行は区切り行の一部とみなす
+    fn next_delimiter(&mut self) -> Option<(&'a str, Delimiter)> {
+        let text = self.rest;
+        let mut lines = message::lines(text);
+        let mut content_end = 0;
+        loop {
+            let start = text.len() - lines.remainder().len();
+            let line = lines.next()?;
+            if let Some(kind) = self.delimiter(line) {
+                self.rest = lines.remainder();
+                return Some((&text[..content_end], kind));
+            }
+            content_end = start + line.len();
+        }
+    }
+}
+
+impl<'a> Iterator for Multipart<'a> {
+    type Item = Result<Part<'a>, HeaderError<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if !self.started {
+            self.started = true;
+            match self.next_delimiter() {
+                Some((_, Delimiter::Part)) => {}
+                _ => {
+                    self.done = true;
+                    return None;
+                }
+            }
+        }
+        match self.next_delimiter() {
+            Some((raw, kind)) => {
+                if let Delimiter::Close = kind {
+                    self.done = true;
+                }
+                Some(Part::parse(raw))
+            }
+            // 終端の区切り行がないまま終わった時は、残り全体を最後のパートとする
+            None => {
+                self.done = true;
+                if self.rest.trim().is_empty() {
+                    None
+                } else {
+                    Some(Part::parse(self.rest))
+                }
+            }
+        }
+    }
+}
+
+// Part::decoded_body()が返すイテレータ
+pub enum Decoded<'a> {
+    Identity(std::str::Bytes<'a>),
+    Base64(Base64Decoder<'a>),
+    QuotedPrintable(QuotedPrintableDecoder<'a>)
+}
+
+impl<'a> Iterator for Decoded<'a> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        match *self {
+            Decoded::Identity(ref mut bytes) => bytes.next(),
+            Decoded::Base64(ref mut decoder) => decoder.next(),
+            Decoded::QuotedPrintable(ref mut decoder) => decoder.next()
+        }
+    }
+}
+
+fn base64_value(b: u8) -> Option<u32> {
+    match b {
+        b'A'..=b'Z' => Some((b - b'A') as u32),
+        b'a'..=b'z' => Some((b - b'a') as u32 + 26),
+        b'0'..=b'9' => Some((b - b'0') as u32 + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None
+    }
+}
+
+// RFC 2045に従い、アルファベット以外の文字（改行など）は読み飛ばす
+pub struct Base64Decoder<'a> {
+    input: std::str::Bytes<'a>,
+    output: [u8; 3],
+    len: usize,
+    pos: usize
+}
+
+impl<'a> Base64Decoder<'a> {
+    fn new(input: std::str::Bytes<'a>) -> Base64Decoder<'a> {
+        Base64Decoder { input, output: [0; 3], len: 0, pos: 0 }
+    }
+
+    // 4文字分（24ビット）を読み、最大3バイトを出力バッファに詰める
+    fn fill(&mut self) -> bool {
+        let mut bits = 0;
+        let mut count = 0;
+        while count < 4 {
+            match self.input.next() {
+                Some(b'=') | None => break,
+                Some(b) => {
+                    if let Some(v) = base64_value(b) {
+                        bits = bits << 6 | v;
+                        count += 1;
+                    }
+                }
+            }
+        }
+        bits <<= 6 * (4 - count);
+        self.output = [(bits >> 16) as u8, (bits >> 8) as u8, bits as u8];
+        self.len = if count >= 2 { count - 1 } else { 0 };
+        self.pos = 0;
+        self.len > 0
+    }
+}
+
+impl<'a> Iterator for Base64Decoder<'a> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.pos == self.len && !self.fill() {
+            return None;
+        }
+        let b = self.output[self.pos];
+        self.pos += 1;
+        Some(b)
+    }
+}
+
+// "=XX"を1バイトに戻し、行末の"="（ソフト改行）を取り除く
+// RFC 2045 6.7 (3)に従い、改行やソフト改行の直前の空白とタブは転送中に付いたものとして取り除く
+// 不正な"="の並びはRFC 2045の推奨どおりそのまま出力する
+pub struct QuotedPrintableDecoder<'a> {
+    input: MultiPeek<std::str::Bytes<'a>>
+}
+
+impl<'a> QuotedPrintableDecoder<'a> {
+    fn new(input: std::str::Bytes<'a>) -> QuotedPrintableDecoder<'a> {
+        QuotedPrintableDecoder { input: MultiPeek::new(input) }
+    }
+
+    // n番目から続く空白とタブの数
+    fn spaces_at(&mut self, n: usize) -> usize {
+        let mut len = 0;
+        while let Some(b' ') | Some(b'\t') = self.input.peek_nth(n + len) {
+            len += 1;
+        }
+        len
+    }
+
+    // n番目から改行が始まっていれば、その長さ
+    fn line_break_at(&mut self, n: usize) -> Option<usize> {
+        match (self.input.peek_nth(n).cloned(), self.input.peek_nth(n + 1).cloned()) {
+            (Some(b'\r'), Some(b'\n')) => Some(2),
+            (Some(b'\n'), _) => Some(1),
+            _ => None
+        }
+    }
+
+    // n番目から"="と改行（間に空白があってもよい）のソフト改行が始まっていれば、その長さ
+    fn soft_break_at(&mut self, n: usize) -> Option<usize> {
+        if self.input.peek_nth(n) != Some(&b'=') {
+            return None;
+        }
+        let spaces = self.spaces_at(n + 1);
+        self.line_break_at(n + 1 + spaces).map(|len| 1 + spaces + len)
+    }
+
+    fn skip(&mut self, n: usize) {
+        for _ in 0..n {
+            self.input.next();
+        }
+    }
+}
+
+fn hex_value(b: Option<&u8>) -> Option<u8> {
+    b.and_then(|&b| (b as char).to_digit(16)).map(|d| d as u8)
+}
+
+impl<'a> Iterator for QuotedPrintableDecoder<'a> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        loop {
+            // 空白は、その後に改行かソフト改行か入力の終わりが続くかを確かめるまで出力しない
+            let spaces = self.spaces_at(0);
+            if spaces > 0 {
+                let at_line_end = self.input.peek_nth(spaces).is_none()
+                    || self.line_break_at(spaces).is_some()
+                    || self.soft_break_at(spaces).is_some();
+                if !at_line_end {
+                    return self.input.next();
+                }
+                self.skip(spaces);
+            }
+            if let Some(len) = self.soft_break_at(0) {
+                self.skip(len);
+                continue;
+            }
+            let b = self.input.next()?;
+            if b != b'=' {
+                return Some(b);
+            }
+            let high = hex_value(self.input.peek_nth(0));
+            let low = hex_value(self.input.peek_nth(1));
+            match (high, low) {
+                (Some(high), Some(low)) => {
+                    self.input.next();
+                    self.input.next();
+                    return Some(high << 4 | low);
+                }
+                _ => return Some(b'=')
+            }
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::vec_deque::{self, VecDeque};
+
+// std::iter::Peekableは1つ先しか覗けないが、MultiPeekは任意の個数だけ先を覗ける
+// 覗いたアイテムは内部のバッファに溜めておき、next()ではバッファから先に返す
+// 入力をクローンする必要がないため、どんなイテレータにも使える
+#[derive(Clone)]
+pub struct MultiPeek<I>
+    where I: Iterator
+{
+    iter: I,
+    buffer: VecDeque<I::Item>
+}
+
+impl<I> MultiPeek<I>
+    where I: Iterator
+{
+    pub fn new(iter: I) -> MultiPeek<I> {
+        MultiPeek { iter, buffer: VecDeque::new() }
+    }
+
+    // バッファにn+1個のアイテムが揃うまで元のイテレータから読み込む
+    fn fill(&mut self, n: usize) -> bool {
+        while self.buffer.len() <= n {
+            match self.iter.next() {
+                Some(item) => self.buffer.push_back(item),
+                None => return false
+            }
+        }
+        true
+    }
+
+    pub fn peek(&mut self) -> Option<&I::Item> {
+        self.peek_nth(0)
+    }
+
+    // n番目（0始まり）のアイテムを消費せずに覗く
+    pub fn peek_nth(&mut self, n: usize) -> Option<&I::Item> {
+        if self.fill(n) {
+            self.buffer.get(n)
+        } else {
+            None
+        }
+    }
+
+    // 先頭からpredicateがtrueを返し続ける範囲のアイテムを、消費せずに順に返す
+    pub fn peek_while<P>(&mut self, mut predicate: P) -> vec_deque::Iter<'_, I::Item>
+        where P: FnMut(&I::Item) -> bool
+    {
+        let mut n = 0;
+        while self.fill(n) && predicate(&self.buffer[n]) {
+            n += 1;
+        }
+        self.buffer.range(..n)
+    }
+
+    // 次のアイテムがpredicateを満たす時だけ消費して返す
+    pub fn next_if<P>(&mut self, predicate: P) -> Option<I::Item>
+        where P: FnOnce(&I::Item) -> bool
+    {
+        match self.peek() {
+            Some(item) if predicate(item) => self.next(),
+            _ => None
+        }
+    }
+
+    pub fn next_if_eq<T>(&mut self, expected: &T) -> Option<I::Item>
+        where T: ?Sized,
+              I::Item: PartialEq<T>
+    {
+        self.next_if(|item| item == expected)
+    }
+
+    // 取り出したアイテムを先頭に戻す。戻したアイテムは次のnext()で再び返される
+    pub fn put_back(&mut self, item: I::Item) {
+        self.buffer.push_front(item);
+    }
+}
+
+impl<I> Iterator for MultiPeek<I>
+    where I: Iterator
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        match self.buffer.pop_front() {
+            Some(item) => Some(item),
+            None => self.iter.next()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        let buffered = self.buffer.len();
+        (lower.saturating_add(buffered), upper.and_then(|n| n.checked_add(buffered)))
+    }
+}
+
+// peekable()と同じ感覚で.multipeek()と書けるようにする
+pub trait MultiPeekExt: Iterator + Sized {
+    fn multipeek(self) -> MultiPeek<Self> {
+        MultiPeek::new(self)
+    }
+}
+
+impl<I> MultiPeekExt for I where I: Iterator {}
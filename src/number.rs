@@ -0,0 +1,20 @@
+use std::iter::Peekable;
+
+// 先頭に続く数字だけを読んで値にする。数字でない文字は消費せずに残す
+pub fn parse_number<I>(tokens: &mut Peekable<I>) -> u32
+    where I: Iterator<Item=char>
+{
+    let mut n = 0;
+    loop {
+        // peek()を使って次の文字をチェックし、取り出したSome(r)が数字の場合だけ消費
+        match tokens.peek() {
+            Some(r) if r.is_ascii_digit() => {
+                n = n * 10 + r.to_digit(10).unwrap();
+            }
+
+            _ => return n
+        }
+
+        tokens.next();
+    }
+}
@@ -0,0 +1,106 @@
+// sum()/product()はオーバーフローするとデバッグビルドではpanicし、リリースビルドでは
+// 黙って桁あふれした値を返す。ここではオーバーフローの扱いを選べるメソッドを用意する
+
+// 全ての組み込みの整数型に実装する演算
+pub trait Integer: Copy {
+    // 桁あふれしないよう、和を求めるときに使う128ビットの型
+    type Wide: Integer;
+
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn saturating_add(self, rhs: Self) -> Self;
+    fn saturating_mul(self, rhs: Self) -> Self;
+    fn wrapping_add(self, rhs: Self) -> Self;
+    fn wrapping_mul(self, rhs: Self) -> Self;
+    fn widen(self) -> Self::Wide;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty => $wide:ty),*) => {
+        $(
+            impl Integer for $t {
+                type Wide = $wide;
+
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn checked_add(self, rhs: Self) -> Option<Self> { <$t>::checked_add(self, rhs) }
+                fn checked_mul(self, rhs: Self) -> Option<Self> { <$t>::checked_mul(self, rhs) }
+                fn saturating_add(self, rhs: Self) -> Self { <$t>::saturating_add(self, rhs) }
+                fn saturating_mul(self, rhs: Self) -> Self { <$t>::saturating_mul(self, rhs) }
+                fn wrapping_add(self, rhs: Self) -> Self { <$t>::wrapping_add(self, rhs) }
+                fn wrapping_mul(self, rhs: Self) -> Self { <$t>::wrapping_mul(self, rhs) }
+                fn widen(self) -> $wide { self as $wide }
+            }
+
+            impl IntegerItem for $t {
+                type Int = $t;
+
+                fn get(self) -> $t { self }
+            }
+
+            impl<'a> IntegerItem for &'a $t {
+                type Int = $t;
+
+                fn get(self) -> $t { *self }
+            }
+        )*
+    }
+}
+
+// sum()と同じく、整数そのものと整数への参照のどちらのイテレータにも使えるようにする
+pub trait IntegerItem {
+    type Int: Integer;
+
+    fn get(self) -> Self::Int;
+}
+
+impl_integer!(u8 => u128, u16 => u128, u32 => u128, u64 => u128, u128 => u128, usize => u128,
+              i8 => i128, i16 => i128, i32 => i128, i64 => i128, i128 => i128, isize => i128);
+
+type Int<I> = <<I as Iterator>::Item as IntegerItem>::Int;
+
+pub trait OverflowExt: Iterator + Sized
+    where Self::Item: IntegerItem
+{
+    // オーバーフローしたらNone。そこで走査をやめる
+    fn checked_sum(mut self) -> Option<Int<Self>> {
+        self.try_fold(Integer::ZERO, |sum: Int<Self>, x| sum.checked_add(x.get()))
+    }
+
+    fn checked_product(mut self) -> Option<Int<Self>> {
+        self.try_fold(Integer::ONE, |product: Int<Self>, x| product.checked_mul(x.get()))
+    }
+
+    // オーバーフローしたら型の最大値（最小値）に張り付く
+    fn saturating_sum(self) -> Int<Self> {
+        self.fold(Integer::ZERO, |sum: Int<Self>, x| sum.saturating_add(x.get()))
+    }
+
+    fn saturating_product(self) -> Int<Self> {
+        self.fold(Integer::ONE, |product: Int<Self>, x| product.saturating_mul(x.get()))
+    }
+
+    // オーバーフローしたら2の補数で折り返す
+    fn wrapping_sum(self) -> Int<Self> {
+        self.fold(Integer::ZERO, |sum: Int<Self>, x| sum.wrapping_add(x.get()))
+    }
+
+    fn wrapping_product(self) -> Int<Self> {
+        self.fold(Integer::ONE, |product: Int<Self>, x| product.wrapping_mul(x.get()))
+    }
+
+    // u128（符号付きならi128）に広げてから足す
+    // 64ビット以下の型ならアイテムが2^64個を超えない限りオーバーフローしない
+    fn widening_sum(mut self) -> Option<<Int<Self> as Integer>::Wide> {
+        self.try_fold(Integer::ZERO, |sum: <Int<Self> as Integer>::Wide, x| sum.checked_add(x.get().widen()))
+    }
+}
+
+impl<I> OverflowExt for I
+    where I: Iterator,
+          I::Item: IntegerItem
+{}
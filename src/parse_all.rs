@@ -0,0 +1,82 @@
+use std::fmt;
+
+// パースに失敗したトークンと、それが元のイテレータの何番目（0始まり）だったか
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rejected<T, E> {
+    pub index: usize,
+    pub token: T,
+    pub error: E
+}
+
+impl<T, E> fmt::Display for Rejected<T, E>
+    where T: fmt::Debug,
+          E: fmt::Display
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "item {} ({:?}): {}", self.index, self.token, self.error)
+    }
+}
+
+impl<T, E> std::error::Error for Rejected<T, E>
+    where T: fmt::Debug,
+          E: fmt::Display + fmt::Debug
+{}
+
+// parse_partition()の結果
+// rejectedには最大max_errors件までしか残さないが、rejected_countは失敗した全件数を数える
+#[derive(Debug, Clone, PartialEq)]
+pub struct Partitioned<T, U, E> {
+    pub parsed: Vec<U>,
+    pub rejected: Vec<Rejected<T, E>>,
+    pub rejected_count: usize
+}
+
+impl<T, U, E> Partitioned<T, U, E> {
+    // 上限を超えたために記録されなかった失敗の件数
+    pub fn unrecorded(&self) -> usize {
+        self.rejected_count - self.rejected.len()
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.rejected_count == 0
+    }
+}
+
+// filter_map(|w| f64::from_str(w).ok())のようにエラーを黙って捨てる代わりに、
+// 何を捨てたかを報告できるようにするアダプタ
+pub trait ParseAllExt: Iterator + Sized {
+    // 全てのアイテムにfを適用し、成功したものと失敗したものに分ける
+    fn parse_partition<U, E, F>(self, max_errors: usize, mut f: F) -> Partitioned<Self::Item, U, E>
+        where F: FnMut(&Self::Item) -> Result<U, E>
+    {
+        let mut result = Partitioned { parsed: Vec::new(), rejected: Vec::new(), rejected_count: 0 };
+        for (index, token) in self.enumerate() {
+            match f(&token) {
+                Ok(value) => result.parsed.push(value),
+                Err(error) => {
+                    if result.rejected.len() < max_errors {
+                        result.rejected.push(Rejected { index, token, error });
+                    }
+                    result.rejected_count += 1;
+                }
+            }
+        }
+        result
+    }
+
+    // collect::<Result<Vec<_>, _>>()と同じく最初の失敗で止まるが、失敗したトークンと位置も返す
+    fn try_parse_all<U, E, F>(self, mut f: F) -> Result<Vec<U>, Rejected<Self::Item, E>>
+        where F: FnMut(&Self::Item) -> Result<U, E>
+    {
+        let mut parsed = Vec::new();
+        for (index, token) in self.enumerate() {
+            match f(&token) {
+                Ok(value) => parsed.push(value),
+                Err(error) => return Err(Rejected { index, token, error })
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+impl<I> ParseAllExt for I where I: Iterator {}
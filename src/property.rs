@@ -0,0 +1,366 @@
+use std::env;
+use std::fmt::{self, Debug};
+use std::iter::{empty, once, successors};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::tree::{self, BinaryTree};
+
+// このシード値を環境変数で与えると、失敗したテストを同じ入力で再現できる
+pub const SEED_VAR: &str = "PROPERTY_SEED";
+
+// 外部クレートを使わないための小さな疑似乱数生成器（SplitMix64）
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // 0以上n未満の値。nが0なら0を返す
+    pub fn below(&mut self, n: u64) -> u64 {
+        if n == 0 { 0 } else { self.next_u64() % n }
+    }
+
+    pub fn one_in(&mut self, n: u64) -> bool {
+        self.below(n) == 0
+    }
+}
+
+// ランダムに生成でき、失敗した値をより単純な値に縮められる型
+// sizeはテストが進むにつれて大きくなり、生成する値の大きさや長さの目安になる
+pub trait Arbitrary: Clone + Debug + 'static {
+    fn arbitrary(rng: &mut Rng, size: usize) -> Self;
+
+    // 自分より単純な候補を、単純なものから順に返す
+    fn shrink(&self) -> Box<dyn Iterator<Item=Self>> {
+        Box::new(empty())
+    }
+}
+
+macro_rules! arbitrary_int {
+    ($($t:ty),*) => {
+        $(
+            impl Arbitrary for $t {
+                fn arbitrary(rng: &mut Rng, size: usize) -> $t {
+                    // 境界付近の値も試せるように、時々は型の全範囲から選ぶ
+                    if rng.one_in(8) {
+                        return rng.next_u64() as $t;
+                    }
+                    // 符号なし整数なら0..=size、符号付きなら-size..=sizeから一様に選ぶ
+                    let low = (<$t>::MIN as i128).max(-(size as i128));
+                    let high = (<$t>::MAX as i128).min(size as i128);
+                    let offset = match ((high - low) as u64).checked_add(1) {
+                        Some(n) => rng.below(n),
+                        None => rng.next_u64()
+                    };
+                    (low + offset as i128) as $t
+                }
+
+                // 0、符号を反転した値、そこから元の値へ半分ずつ近づく値の順
+                fn shrink(&self) -> Box<dyn Iterator<Item=$t>> {
+                    let x = *self;
+                    let mut candidates = Vec::new();
+                    if x != 0 {
+                        candidates.push(0);
+                    }
+                    if let Some(neg) = x.checked_neg() {
+                        if neg > x {
+                            candidates.push(neg);
+                        }
+                    }
+                    let mut d = x / 2;
+                    while d != 0 {
+                        candidates.push(x - d);
+                        d /= 2;
+                    }
+                    Box::new(candidates.into_iter())
+                }
+            }
+        )*
+    }
+}
+
+arbitrary_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl Arbitrary for bool {
+    fn arbitrary(rng: &mut Rng, _size: usize) -> bool {
+        rng.one_in(2)
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item=bool>> {
+        if *self { Box::new(once(false)) } else { Box::new(empty()) }
+    }
+}
+
+impl Arbitrary for char {
+    // ほとんどは表示可能なASCII文字で、時々それ以外のUnicodeスカラー値を混ぜる
+    fn arbitrary(rng: &mut Rng, _size: usize) -> char {
+        if !rng.one_in(8) {
+            return (b' ' + rng.below(95) as u8) as char;
+        }
+        loop {
+            if let Some(c) = std::char::from_u32(rng.below(0x11_0000) as u32) {
+                return c;
+            }
+        }
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item=char>> {
+        if *self == 'a' { Box::new(empty()) } else { Box::new(once('a')) }
+    }
+}
+
+impl<T> Arbitrary for Vec<T>
+    where T: Arbitrary
+{
+    fn arbitrary(rng: &mut Rng, size: usize) -> Vec<T> {
+        let len = rng.below(size as u64 + 1) as usize;
+        (0..len).map(|_| T::arbitrary(rng, size)).collect()
+    }
+
+    // 要素をまとめて取り除く候補（全部、半分ずつ、...、1つずつ）の後に、各要素を縮めた候補
+    fn shrink(&self) -> Box<dyn Iterator<Item=Vec<T>>> {
+        let len = self.len();
+        let v = self.clone();
+        let removals = successors(Some(len), |&k| if k > 1 { Some(k / 2) } else { None })
+            .take_while(|&k| k > 0)
+            .flat_map(move |k| {
+                let v = v.clone();
+                (0..).map(move |i| i * k)
+                    .take_while(move |&start| start + k <= len)
+                    .map(move |start| {
+                        let mut smaller = v.clone();
+                        smaller.drain(start..start + k);
+                        smaller
+                    })
+            });
+
+        let v = self.clone();
+        let elements = (0..len).flat_map(move |i| {
+            let base = v.clone();
+            v[i].shrink().map(move |x| {
+                let mut smaller = base.clone();
+                smaller[i] = x;
+                smaller
+            })
+        });
+
+        Box::new(removals.chain(elements))
+    }
+}
+
+impl Arbitrary for String {
+    fn arbitrary(rng: &mut Rng, size: usize) -> String {
+        Vec::<char>::arbitrary(rng, size).into_iter().collect()
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item=String>> {
+        let chars: Vec<char> = self.chars().collect();
+        Box::new(chars.shrink().map(|chars| chars.into_iter().collect()))
+    }
+}
+
+impl<A, B> Arbitrary for (A, B)
+    where A: Arbitrary,
+          B: Arbitrary
+{
+    fn arbitrary(rng: &mut Rng, size: usize) -> (A, B) {
+        (A::arbitrary(rng, size), B::arbitrary(rng, size))
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item=(A, B)>> {
+        let (a, b) = self.clone();
+        Box::new(self.0.shrink().map(move |x| (x, b.clone()))
+                 .chain(self.1.shrink().map(move |y| (a.clone(), y))))
+    }
+}
+
+impl<A, B, C> Arbitrary for (A, B, C)
+    where A: Arbitrary,
+          B: Arbitrary,
+          C: Arbitrary
+{
+    fn arbitrary(rng: &mut Rng, size: usize) -> (A, B, C) {
+        (A::arbitrary(rng, size), B::arbitrary(rng, size), C::arbitrary(rng, size))
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item=(A, B, C)>> {
+        let (a1, b1, c1) = self.clone();
+        let (a2, b2, c2) = self.clone();
+        Box::new(self.0.shrink().map(move |x| (x, b1.clone(), c1.clone()))
+                 .chain(self.1.shrink().map(move |y| (a1.clone(), y, c2.clone())))
+                 .chain(self.2.shrink().map(move |z| (a2.clone(), b2.clone(), z))))
+    }
+}
+
+// 最大n個のノードを持つ、形がランダムな木
+fn arbitrary_tree<T>(rng: &mut Rng, nodes: usize, size: usize) -> BinaryTree<T>
+    where T: Arbitrary
+{
+    if nodes == 0 {
+        return BinaryTree::Empty;
+    }
+    let left = rng.below(nodes as u64) as usize;
+    let left_tree = arbitrary_tree(rng, left, size);
+    let element = T::arbitrary(rng, size);
+    let right_tree = arbitrary_tree(rng, nodes - 1 - left, size);
+    tree::make_node(left_tree, element, right_tree)
+}
+
+impl<T> Arbitrary for BinaryTree<T>
+    where T: Arbitrary
+{
+    fn arbitrary(rng: &mut Rng, size: usize) -> BinaryTree<T> {
+        let nodes = rng.below(size as u64 + 1) as usize;
+        arbitrary_tree(rng, nodes, size)
+    }
+
+    // 空の木、左右の部分木だけの木、根の要素を縮めた木、部分木を縮めた木の順
+    fn shrink(&self) -> Box<dyn Iterator<Item=BinaryTree<T>>> {
+        let node = match *self {
+            BinaryTree::Empty => return Box::new(empty()),
+            BinaryTree::NonEmpty(ref node) => (**node).clone()
+        };
+        let subtrees = vec![BinaryTree::Empty, node.left.clone(), node.right.clone()];
+
+        let (n1, n2, n3) = (node.clone(), node.clone(), node.clone());
+        let elements = node.element.shrink()
+            .map(move |e| tree::make_node(n1.left.clone(), e, n1.right.clone()));
+        let lefts = node.left.shrink()
+            .map(move |l| tree::make_node(l, n2.element.clone(), n2.right.clone()));
+        let rights = node.right.shrink()
+            .map(move |r| tree::make_node(n3.left.clone(), n3.element.clone(), r));
+
+        Box::new(subtrees.into_iter().chain(elements).chain(lefts).chain(rights))
+    }
+}
+
+// 検査の設定。Config::new()のシード値は環境変数PROPERTY_SEEDか、なければ現在時刻から決める
+#[derive(Debug, Clone)]
+pub struct Config {
+    cases: usize,
+    max_size: usize,
+    seed: u64,
+    max_shrinks: usize
+}
+
+impl Config {
+    pub fn new() -> Config {
+        let seed = env::var(SEED_VAR).ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_else(|| {
+                SystemTime::now().duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_nanos() as u64)
+                    .unwrap_or(0)
+            });
+        Config { cases: 100, max_size: 100, seed, max_shrinks: 10_000 }
+    }
+
+    pub fn cases(mut self, cases: usize) -> Self {
+        self.cases = cases;
+        self
+    }
+
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    // 縮める候補を試す回数の上限
+    pub fn max_shrinks(mut self, max_shrinks: usize) -> Self {
+        self.max_shrinks = max_shrinks;
+        self
+    }
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config::new()
+    }
+}
+
+// 性質を満たさなかった入力。shrunkはoriginalをこれ以上縮められないところまで縮めたもの
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failure<T> {
+    pub seed: u64,
+    pub case: usize,
+    pub original: T,
+    pub shrunk: T,
+    pub shrinks: usize
+}
+
+impl<T: Debug> fmt::Display for Failure<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "property falsified at case {} (rerun with {}={}): {:?}, shrunk to {:?} in {} step(s)",
+               self.case, SEED_VAR, self.seed, self.original, self.shrunk, self.shrinks)
+    }
+}
+
+impl<T: Debug> std::error::Error for Failure<T> {}
+
+// 性質propをランダムな入力で繰り返し検査し、満たさない入力があれば縮めて返す
+pub fn check<T, F>(config: &Config, prop: F) -> Result<(), Failure<T>>
+    where T: Arbitrary,
+          F: Fn(&T) -> bool
+{
+    let mut rng = Rng::new(config.seed);
+    for case in 0..config.cases {
+        let size = if config.cases > 1 {
+            case * config.max_size / (config.cases - 1)
+        } else {
+            config.max_size
+        };
+        let original = T::arbitrary(&mut rng, size);
+        if prop(&original) {
+            continue;
+        }
+
+        // 性質を満たさない候補が見つかる限り、より単純な値へ移っていく
+        let mut shrunk = original.clone();
+        let mut shrinks = 0;
+        let mut tries = 0;
+        'shrinking: loop {
+            for candidate in shrunk.shrink() {
+                tries += 1;
+                if tries > config.max_shrinks {
+                    break 'shrinking;
+                }
+                if !prop(&candidate) {
+                    shrunk = candidate;
+                    shrinks += 1;
+                    continue 'shrinking;
+                }
+            }
+            break;
+        }
+
+        return Err(Failure { seed: config.seed, case, original, shrunk, shrinks });
+    }
+    Ok(())
+}
+
+// 既定の設定でcheck()を行い、失敗したら再現用のシード値と縮めた入力を表示してpanicする
+pub fn quickcheck<T, F>(prop: F)
+    where T: Arbitrary,
+          F: Fn(&T) -> bool
+{
+    if let Err(failure) = check(&Config::new(), prop) {
+        panic!("{}", failure);
+    }
+}
@@ -0,0 +1,150 @@
+use std::cmp::Ordering;
+use std::iter::FromIterator;
+
+use crate::float_ord::total_cmp;
+
+// collect()で作る場合の誤差
+pub const DEFAULT_EPSILON: f64 = 0.001;
+
+// 要約に残す値と、その値の順位の幅
+#[derive(Debug, Clone, Copy)]
+struct Tuple {
+    value: f64,
+    // 1つ前の値との最小順位の差
+    g: u64,
+    // 最大順位と最小順位の差
+    delta: u64
+}
+
+// Greenwald-Khannaのストリーミング分位点スケッチ
+// 全ての値を保持して並べ替える代わりに、O(1/ε log εn)個の値だけを残す
+// quantile(φ)が返す値の順位は、正確な順位φnから±εnの範囲に収まる
+// 値の順序にはtotal_cmp()を使うので、NaNは最大の値として扱われる
+#[derive(Debug, Clone)]
+pub struct QuantileSketch {
+    epsilon: f64,
+    count: u64,
+    summary: Vec<Tuple>
+}
+
+impl QuantileSketch {
+    pub fn new(epsilon: f64) -> Self {
+        assert!(epsilon > 0.0 && epsilon < 1.0, "epsilon must be in (0, 1): {}", epsilon);
+        QuantileSketch { epsilon, count: 0, summary: Vec::new() }
+    }
+
+    pub fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    // 要約に残っている値の数
+    pub fn summary_len(&self) -> usize {
+        self.summary.len()
+    }
+
+    // 1つの値が持てる順位の幅の上限
+    fn band(&self) -> u64 {
+        (2.0 * self.epsilon * self.count as f64).floor() as u64
+    }
+
+    pub fn insert(&mut self, value: f64) {
+        let index = self.summary
+            .binary_search_by(|t| match total_cmp(&t.value, &value) {
+                Ordering::Equal => Ordering::Less,
+                ordering => ordering
+            })
+            .unwrap_or_else(|i| i);
+        // 最小と最大の値は順位が正確に分かる
+        let delta = if index == 0 || index == self.summary.len() { 0 } else { self.band() };
+        self.summary.insert(index, Tuple { value, g: 1, delta });
+        self.count += 1;
+
+        let period = (1.0 / (2.0 * self.epsilon)).floor().max(1.0) as u64;
+        if self.count.is_multiple_of(period) {
+            self.compress();
+        }
+    }
+
+    // 順位の幅がbandに収まる限り、隣り合う値をまとめる
+    fn compress(&mut self) {
+        let band = self.band();
+        let len = self.summary.len();
+        if len < 3 {
+            return;
+        }
+        for i in (1..len - 1).rev() {
+            let (current, next) = (self.summary[i], self.summary[i + 1]);
+            if current.g + next.g + next.delta <= band {
+                self.summary[i + 1].g += current.g;
+                self.summary.remove(i);
+            }
+        }
+    }
+
+    // 0.0 <= phi <= 1.0の分位点。値が1つもなければNone
+    pub fn quantile(&self, phi: f64) -> Option<f64> {
+        if self.summary.is_empty() {
+            return None;
+        }
+        let phi = phi.clamp(0.0, 1.0);
+        let rank = ((phi * self.count as f64).ceil() as u64).max(1);
+        let bound = (self.epsilon * self.count as f64).floor() as u64;
+
+        let mut min_rank = 0;
+        let mut previous = self.summary[0].value;
+        for t in &self.summary {
+            min_rank += t.g;
+            if min_rank + t.delta > rank + bound {
+                return Some(previous);
+            }
+            previous = t.value;
+        }
+        Some(previous)
+    }
+
+    pub fn median(&self) -> Option<f64> {
+        self.quantile(0.5)
+    }
+}
+
+impl Default for QuantileSketch {
+    fn default() -> Self {
+        QuantileSketch::new(DEFAULT_EPSILON)
+    }
+}
+
+impl Extend<f64> for QuantileSketch {
+    fn extend<T: IntoIterator<Item=f64>>(&mut self, iter: T) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<'a> Extend<&'a f64> for QuantileSketch {
+    fn extend<T: IntoIterator<Item=&'a f64>>(&mut self, iter: T) {
+        self.extend(iter.into_iter().cloned());
+    }
+}
+
+impl FromIterator<f64> for QuantileSketch {
+    fn from_iter<T: IntoIterator<Item=f64>>(iter: T) -> Self {
+        let mut sketch = QuantileSketch::default();
+        sketch.extend(iter);
+        sketch
+    }
+}
+
+impl<'a> FromIterator<&'a f64> for QuantileSketch {
+    fn from_iter<T: IntoIterator<Item=&'a f64>>(iter: T) -> Self {
+        iter.into_iter().cloned().collect()
+    }
+}
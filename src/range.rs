@@ -0,0 +1,19 @@
+// startからend（含まない）までのi32を順に返すイテレータ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct I32Range {
+    pub start: i32,
+    pub end: i32
+}
+
+impl Iterator for I32Range {
+    type Item = i32;
+    fn next(&mut self) -> Option<i32> {
+        if self.start >= self.end {
+            return None;
+        }
+
+        let result = Some(self.start);
+        self.start += 1;
+        result
+    }
+}
@@ -0,0 +1,681 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+
+// パイプラインを流れる値
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Str(String),
+    Pair(Box<Value>, Box<Value>)
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Value::Int(n) => write!(f, "{}", n),
+            Value::Str(ref s) => write!(f, "{:?}", s),
+            Value::Pair(ref a, ref b) => write!(f, "({}, {})", a, b)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplError {
+    UnknownCommand(String),
+    UnknownFunction { command: String, name: String },
+    MissingArgument { command: String, expected: &'static str },
+    InvalidArgument { command: String, argument: String, expected: &'static str },
+    Type { function: &'static str, value: Value },
+    UnterminatedQuote,
+    NoSource,
+    Limit { source: String, limit: usize },
+    Io(String)
+}
+
+impl fmt::Display for ReplError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ReplError::UnknownCommand(ref name) =>
+                write!(f, "unknown command `{}` (try `help`)", name),
+            ReplError::UnknownFunction { ref command, ref name } =>
+                write!(f, "{}: unknown function `{}`", command, name),
+            ReplError::MissingArgument { ref command, expected } =>
+                write!(f, "{}: expected {}", command, expected),
+            ReplError::InvalidArgument { ref command, ref argument, expected } =>
+                write!(f, "{}: expected {}, found `{}`", command, expected, argument),
+            ReplError::Type { function, ref value } =>
+                write!(f, "{}: cannot be applied to {}", function, value),
+            ReplError::UnterminatedQuote => write!(f, "unterminated string"),
+            ReplError::NoSource => write!(f, "start with a source first (try `help`)"),
+            ReplError::Limit { ref source, limit } =>
+                write!(f, "{}: stopped after {} items (try `take N`)", source, limit),
+            ReplError::Io(ref message) => write!(f, "{}", message)
+        }
+    }
+}
+
+impl std::error::Error for ReplError {}
+
+pub type Item = Result<Value, ReplError>;
+pub type Pipeline = Box<dyn Iterator<Item=Item>>;
+
+pub type SourceFn = fn(&[String]) -> Result<Pipeline, ReplError>;
+pub type AdapterFn = fn(&Registry, Pipeline, &[String]) -> Result<Pipeline, ReplError>;
+pub type MapFn = fn(Value) -> Result<Value, ReplError>;
+pub type PredicateFn = fn(&Value) -> Result<bool, ReplError>;
+
+struct Entry<F> {
+    help: &'static str,
+    f: F
+}
+
+// 名前から、ソース、アダプタ、mapに渡す関数、filterなどに渡す述語を引く表
+pub struct Registry {
+    sources: BTreeMap<&'static str, Entry<SourceFn>>,
+    adapters: BTreeMap<&'static str, Entry<AdapterFn>>,
+    maps: BTreeMap<&'static str, Entry<MapFn>>,
+    predicates: BTreeMap<&'static str, Entry<PredicateFn>>
+}
+
+impl Registry {
+    pub fn new() -> Registry {
+        Registry {
+            sources: BTreeMap::new(),
+            adapters: BTreeMap::new(),
+            maps: BTreeMap::new(),
+            predicates: BTreeMap::new()
+        }
+    }
+
+    pub fn source(mut self, name: &'static str, help: &'static str, f: SourceFn) -> Self {
+        self.sources.insert(name, Entry { help, f });
+        self
+    }
+
+    pub fn adapter(mut self, name: &'static str, help: &'static str, f: AdapterFn) -> Self {
+        self.adapters.insert(name, Entry { help, f });
+        self
+    }
+
+    pub fn map(mut self, name: &'static str, help: &'static str, f: MapFn) -> Self {
+        self.maps.insert(name, Entry { help, f });
+        self
+    }
+
+    pub fn predicate(mut self, name: &'static str, help: &'static str, f: PredicateFn) -> Self {
+        self.predicates.insert(name, Entry { help, f });
+        self
+    }
+
+    // 標準のソース、アダプタ、関数を全て登録したもの
+    pub fn standard() -> Registry {
+        Registry::new()
+            .source("range", "range A..B | A..=B | A..  整数の範囲", range)
+            .source("words", "words \"TEXT\"  空白で区切った単語", words)
+            .source("chars", "chars \"TEXT\"  1文字ずつ", chars)
+            .source("lines", "lines FILE  ファイルの各行", lines)
+            .adapter("map", "map FN  各値にFNを適用する", map)
+            .adapter("filter", "filter PRED  PREDを満たす値だけを残す", filter)
+            .adapter("take", "take N  先頭からN個", take)
+            .adapter("skip", "skip N  先頭のN個を飛ばす", skip)
+            .adapter("step_by", "step_by N  N個おきに取り出す", step_by)
+            .adapter("take_while", "take_while PRED  PREDを満たす間だけ取り出す", take_while)
+            .adapter("skip_while", "skip_while PRED  PREDを満たす間は飛ばす", skip_while)
+            .adapter("enumerate", "enumerate  (番号, 値)の組にする", enumerate)
+            .adapter("zip", "zip SOURCE...  別のソースと組にする", zip)
+            .adapter("chain", "chain SOURCE...  別のソースを後ろに繋げる", chain)
+            .adapter("cycle", "cycle  無限に繰り返す", cycle)
+            .map("sq", "2乗", |v| int_map("sq", v, |n| n.checked_mul(n)))
+            .map("double", "2倍", |v| int_map("double", v, |n| n.checked_mul(2)))
+            .map("inc", "1を足す", |v| int_map("inc", v, |n| n.checked_add(1)))
+            .map("dec", "1を引く", |v| int_map("dec", v, |n| n.checked_sub(1)))
+            .map("neg", "符号を反転", |v| int_map("neg", v, |n| n.checked_neg()))
+            .map("abs", "絶対値", |v| int_map("abs", v, |n| n.checked_abs()))
+            .map("len", "文字数", |v| str_map("len", v, |s| Value::Int(s.chars().count() as i64)))
+            .map("upper", "大文字にする", |v| str_map("upper", v, |s| Value::Str(s.to_uppercase())))
+            .map("lower", "小文字にする", |v| str_map("lower", v, |s| Value::Str(s.to_lowercase())))
+            .map("trim", "前後の空白を除く", |v| str_map("trim", v, |s| Value::Str(s.trim().to_string())))
+            .map("rev", "文字を逆順にする", |v| str_map("rev", v, |s| Value::Str(s.chars().rev().collect())))
+            .map("parse", "整数として読む", parse)
+            .map("str", "文字列にする", |v| Ok(match v {
+                Value::Str(s) => Value::Str(s),
+                other => Value::Str(other.to_string())
+            }))
+            .map("first", "組の1つ目", |v| match v {
+                Value::Pair(a, _) => Ok(*a),
+                other => Err(ReplError::Type { function: "first", value: other })
+            })
+            .map("second", "組の2つ目", |v| match v {
+                Value::Pair(_, b) => Ok(*b),
+                other => Err(ReplError::Type { function: "second", value: other })
+            })
+            .predicate("even", "偶数", |v| int_test("even", v, |n| n % 2 == 0))
+            .predicate("odd", "奇数", |v| int_test("odd", v, |n| n % 2 != 0))
+            .predicate("positive", "正の数", |v| int_test("positive", v, |n| n > 0))
+            .predicate("negative", "負の数", |v| int_test("negative", v, |n| n < 0))
+            .predicate("nonempty", "空でない文字列", |v| str_test("nonempty", v, |s| !s.is_empty()))
+            .predicate("alpha", "英字だけの文字列", |v| str_test("alpha", v, |s| s.chars().all(char::is_alphabetic)))
+            .predicate("numeric", "数字だけの文字列", |v| str_test("numeric", v, |s| s.chars().all(|c| c.is_ascii_digit())))
+            .predicate("capitalized", "大文字で始まる文字列", |v| str_test("capitalized", v, |s| s.starts_with(char::is_uppercase)))
+    }
+
+    // args[0]をソースの名前として、新しいパイプラインを作る
+    pub fn build_source(&self, args: &[String]) -> Result<Pipeline, ReplError> {
+        let (name, rest) = args.split_first().ok_or(ReplError::NoSource)?;
+        match self.sources.get(name.as_str()) {
+            Some(entry) => Ok(Box::new(Limited::new(name.clone(), (entry.f)(rest)?))),
+            None if self.adapters.contains_key(name.as_str()) => Err(ReplError::NoSource),
+            None => Err(ReplError::UnknownCommand(name.clone()))
+        }
+    }
+
+    // args[0]をアダプタの名前として、パイプラインに繋げる
+    pub fn apply(&self, pipeline: Pipeline, args: &[String]) -> Result<Pipeline, ReplError> {
+        let (name, rest) = args.split_first().ok_or(ReplError::NoSource)?;
+        match self.adapters.get(name.as_str()) {
+            Some(entry) => (entry.f)(self, pipeline, rest),
+            None => Err(ReplError::UnknownCommand(name.clone()))
+        }
+    }
+
+    pub fn is_source(&self, name: &str) -> bool {
+        self.sources.contains_key(name)
+    }
+
+    fn map_fn(&self, command: &str, args: &[String]) -> Result<MapFn, ReplError> {
+        let name = argument(command, args, "a function name")?;
+        self.maps.get(name.as_str())
+            .map(|entry| entry.f)
+            .ok_or_else(|| ReplError::UnknownFunction { command: command.to_string(), name: name.clone() })
+    }
+
+    fn predicate_fn(&self, command: &str, args: &[String]) -> Result<PredicateFn, ReplError> {
+        let name = argument(command, args, "a predicate name")?;
+        self.predicates.get(name.as_str())
+            .map(|entry| entry.f)
+            .ok_or_else(|| ReplError::UnknownFunction { command: command.to_string(), name: name.clone() })
+    }
+
+    pub fn help(&self) -> String {
+        fn section<F>(out: &mut String, title: &str, entries: &BTreeMap<&'static str, Entry<F>>) {
+            out.push_str(title);
+            out.push('\n');
+            for (name, entry) in entries {
+                out.push_str(&format!("  {:<12} {}\n", name, entry.help));
+            }
+        }
+        let mut out = String::new();
+        section(&mut out, "sources:", &self.sources);
+        section(&mut out, "adapters:", &self.adapters);
+        section(&mut out, "functions (map):", &self.maps);
+        section(&mut out, "predicates (filter, take_while, skip_while):", &self.predicates);
+        out.push_str("commands:\n  show  pop  clear  limit N  count  sum  help  quit\n");
+        out.push_str("stages can be joined with `|`, e.g. range 0.. | filter even | map sq | take 5\n");
+        out
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Registry {
+        Registry::standard()
+    }
+}
+
+fn int_map<F>(function: &'static str, value: Value, f: F) -> Result<Value, ReplError>
+    where F: Fn(i64) -> Option<i64>
+{
+    match value {
+        Value::Int(n) => f(n).map(Value::Int).ok_or(ReplError::Type { function, value }),
+        other => Err(ReplError::Type { function, value: other })
+    }
+}
+
+fn str_map<F>(function: &'static str, value: Value, f: F) -> Result<Value, ReplError>
+    where F: Fn(&str) -> Value
+{
+    match value {
+        Value::Str(ref s) => Ok(f(s)),
+        other => Err(ReplError::Type { function, value: other })
+    }
+}
+
+fn int_test<F>(function: &'static str, value: &Value, f: F) -> Result<bool, ReplError>
+    where F: Fn(i64) -> bool
+{
+    match *value {
+        Value::Int(n) => Ok(f(n)),
+        ref other => Err(ReplError::Type { function, value: other.clone() })
+    }
+}
+
+fn str_test<F>(function: &'static str, value: &Value, f: F) -> Result<bool, ReplError>
+    where F: Fn(&str) -> bool
+{
+    match *value {
+        Value::Str(ref s) => Ok(f(s)),
+        ref other => Err(ReplError::Type { function, value: other.clone() })
+    }
+}
+
+fn parse(value: Value) -> Result<Value, ReplError> {
+    match value {
+        Value::Str(ref s) => match s.trim().parse() {
+            Ok(n) => Ok(Value::Int(n)),
+            Err(_) => Err(ReplError::Type { function: "parse", value: value.clone() })
+        },
+        other => Err(ReplError::Type { function: "parse", value: other })
+    }
+}
+
+fn argument<'a>(command: &str, args: &'a [String], expected: &'static str) -> Result<&'a String, ReplError> {
+    args.first().ok_or_else(|| ReplError::MissingArgument { command: command.to_string(), expected })
+}
+
+fn count_argument(command: &str, args: &[String]) -> Result<usize, ReplError> {
+    let arg = argument(command, args, "a count")?;
+    arg.parse().map_err(|_| ReplError::InvalidArgument {
+        command: command.to_string(),
+        argument: arg.clone(),
+        expected: "a count"
+    })
+}
+
+fn range(args: &[String]) -> Result<Pipeline, ReplError> {
+    let arg = argument("range", args, "a range like 0..10")?;
+    let invalid = || ReplError::InvalidArgument {
+        command: "range".to_string(),
+        argument: arg.clone(),
+        expected: "a range like 0..10, 1..=5 or 0.."
+    };
+    let (start, end) = match arg.find("..") {
+        Some(i) => (&arg[..i], &arg[i + 2..]),
+        None => return Err(invalid())
+    };
+    let start: i64 = start.parse().map_err(|_| invalid())?;
+    let values: Box<dyn Iterator<Item=i64>> = if end.is_empty() {
+        Box::new(start..=i64::MAX)
+    } else if let Some(end) = end.strip_prefix('=') {
+        Box::new(start..=end.parse().map_err(|_| invalid())?)
+    } else {
+        Box::new(start..end.parse().map_err(|_| invalid())?)
+    };
+    Ok(Box::new(values.map(|n| Ok(Value::Int(n)))))
+}
+
+fn words(args: &[String]) -> Result<Pipeline, ReplError> {
+    let words: Vec<Item> = args.iter()
+        .flat_map(|arg| arg.split_whitespace())
+        .map(|w| Ok(Value::Str(w.to_string())))
+        .collect();
+    Ok(Box::new(words.into_iter()))
+}
+
+fn chars(args: &[String]) -> Result<Pipeline, ReplError> {
+    let text = argument("chars", args, "a string")?;
+    let chars: Vec<Item> = text.chars().map(|c| Ok(Value::Str(c.to_string()))).collect();
+    Ok(Box::new(chars.into_iter()))
+}
+
+// ファイルは読み込まず、行のイテレータのままパイプラインに繋ぐ
+fn lines(args: &[String]) -> Result<Pipeline, ReplError> {
+    let path = argument("lines", args, "a file name")?;
+    let file = File::open(path).map_err(|e| ReplError::Io(format!("lines: {}: {}", path, e)))?;
+    Ok(Box::new(BufReader::new(file).lines().map(|line| {
+        line.map(Value::Str).map_err(|e| ReplError::Io(e.to_string()))
+    })))
+}
+
+fn map(registry: &Registry, pipeline: Pipeline, args: &[String]) -> Result<Pipeline, ReplError> {
+    let f = registry.map_fn("map", args)?;
+    Ok(Box::new(pipeline.map(move |item| item.and_then(f))))
+}
+
+fn filter(registry: &Registry, pipeline: Pipeline, args: &[String]) -> Result<Pipeline, ReplError> {
+    let p = registry.predicate_fn("filter", args)?;
+    Ok(Box::new(pipeline.filter_map(move |item| match item {
+        Ok(v) => match p(&v) {
+            Ok(true) => Some(Ok(v)),
+            Ok(false) => None,
+            Err(e) => Some(Err(e))
+        },
+        Err(e) => Some(Err(e))
+    })))
+}
+
+fn take(_: &Registry, pipeline: Pipeline, args: &[String]) -> Result<Pipeline, ReplError> {
+    Ok(Box::new(pipeline.take(count_argument("take", args)?)))
+}
+
+fn skip(_: &Registry, pipeline: Pipeline, args: &[String]) -> Result<Pipeline, ReplError> {
+    Ok(Box::new(pipeline.skip(count_argument("skip", args)?)))
+}
+
+fn step_by(_: &Registry, pipeline: Pipeline, args: &[String]) -> Result<Pipeline, ReplError> {
+    match count_argument("step_by", args)? {
+        0 => Err(ReplError::InvalidArgument {
+            command: "step_by".to_string(),
+            argument: "0".to_string(),
+            expected: "a positive count"
+        }),
+        n => Ok(Box::new(pipeline.step_by(n)))
+    }
+}
+
+fn take_while(registry: &Registry, pipeline: Pipeline, args: &[String]) -> Result<Pipeline, ReplError> {
+    let p = registry.predicate_fn("take_while", args)?;
+    // エラーはそこで打ち切って1度だけ返す
+    Ok(Box::new(pipeline.scan(false, move |failed, item| {
+        if *failed {
+            return None;
+        }
+        match item.and_then(|v| p(&v).map(|keep| (keep, v))) {
+            Ok((true, v)) => Some(Ok(v)),
+            Ok((false, _)) => None,
+            Err(e) => {
+                *failed = true;
+                Some(Err(e))
+            }
+        }
+    })))
+}
+
+fn skip_while(registry: &Registry, pipeline: Pipeline, args: &[String]) -> Result<Pipeline, ReplError> {
+    let p = registry.predicate_fn("skip_while", args)?;
+    // 述語がエラーになった値は飛ばさず、後ろのアダプタに渡す
+    Ok(Box::new(pipeline.skip_while(move |item| match *item {
+        Ok(ref v) => p(v).unwrap_or(false),
+        Err(_) => false
+    })))
+}
+
+fn enumerate(_: &Registry, pipeline: Pipeline, _: &[String]) -> Result<Pipeline, ReplError> {
+    Ok(Box::new(pipeline.enumerate().map(|(i, item)| {
+        item.map(|v| Value::Pair(Box::new(Value::Int(i as i64)), Box::new(v)))
+    })))
+}
+
+fn zip(registry: &Registry, pipeline: Pipeline, args: &[String]) -> Result<Pipeline, ReplError> {
+    let other = registry.build_source(args)?;
+    Ok(Box::new(pipeline.zip(other).map(|(a, b)| Ok(Value::Pair(Box::new(a?), Box::new(b?))))))
+}
+
+fn chain(registry: &Registry, pipeline: Pipeline, args: &[String]) -> Result<Pipeline, ReplError> {
+    let other = registry.build_source(args)?;
+    Ok(Box::new(pipeline.chain(other)))
+}
+
+// 無限のパイプラインを読み続けないよう、1つのソース（とcycle）から取り出す値の数の上限
+// filterで値が全て捨てられても止まるように、出力ではなく取り出した数で数える
+pub const MAX_PULLED: usize = 1_000_000;
+
+// 取り出した値がMAX_PULLED個を超えたら、その後はずっとエラーを返す
+// skipやstep_byに読み捨てられても、エラーが最後のステージまで届くようにするため
+struct Limited {
+    source: String,
+    inner: Pipeline,
+    pulled: usize
+}
+
+impl Limited {
+    fn new(source: String, inner: Pipeline) -> Limited {
+        Limited { source, inner, pulled: 0 }
+    }
+}
+
+impl Iterator for Limited {
+    type Item = Item;
+
+    fn next(&mut self) -> Option<Item> {
+        if self.pulled <= MAX_PULLED {
+            let item = self.inner.next()?;
+            self.pulled += 1;
+            if self.pulled <= MAX_PULLED {
+                return Some(item);
+            }
+        }
+        Some(Err(ReplError::Limit { source: self.source.clone(), limit: MAX_PULLED }))
+    }
+}
+
+// Box<dyn Iterator>はCloneできないので、Iterator::cycle()の代わりに一度通った値を覚えて繰り返す
+struct Cycle {
+    inner: Option<Pipeline>,
+    seen: Vec<Item>,
+    pos: usize
+}
+
+impl Iterator for Cycle {
+    type Item = Item;
+
+    fn next(&mut self) -> Option<Item> {
+        if let Some(ref mut inner) = self.inner {
+            match inner.next() {
+                Some(item) => {
+                    self.seen.push(item.clone());
+                    return Some(item);
+                }
+                None => self.inner = None
+            }
+        }
+        if self.seen.is_empty() {
+            return None;
+        }
+        let item = self.seen[self.pos % self.seen.len()].clone();
+        self.pos += 1;
+        Some(item)
+    }
+}
+
+fn cycle(_: &Registry, pipeline: Pipeline, _: &[String]) -> Result<Pipeline, ReplError> {
+    let cycle = Cycle { inner: Some(pipeline), seen: Vec::new(), pos: 0 };
+    Ok(Box::new(Limited::new("cycle".to_string(), Box::new(cycle))))
+}
+
+// 1行を'|'で区切られたステージに分け、各ステージを単語に分ける
+// ダブルクォートで囲んだ部分は空白や'|'を含む1つの単語になる
+pub fn parse_line(line: &str) -> Result<Vec<Vec<String>>, ReplError> {
+    let mut stages = vec![Vec::new()];
+    let mut chars = line.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '|' {
+            chars.next();
+            stages.push(Vec::new());
+        } else if c == '"' {
+            chars.next();
+            let mut word = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => match chars.next() {
+                        Some('n') => word.push('\n'),
+                        Some('t') => word.push('\t'),
+                        Some(c) => word.push(c),
+                        None => return Err(ReplError::UnterminatedQuote)
+                    },
+                    Some(c) => word.push(c),
+                    None => return Err(ReplError::UnterminatedQuote)
+                }
+            }
+            stages.last_mut().unwrap().push(word);
+        } else {
+            let mut word = String::new();
+            while let Some(c) = chars.next_if(|&c| !c.is_whitespace() && c != '|' && c != '"') {
+                word.push(c);
+            }
+            stages.last_mut().unwrap().push(word);
+        }
+    }
+    stages.retain(|stage| !stage.is_empty());
+    Ok(stages)
+}
+
+// parse_line()で同じ単語に戻るように、必要なら引用符を付けて並べる
+fn display_stage(stage: &[String]) -> String {
+    let words: Vec<String> = stage.iter()
+        .map(|word| {
+            if word.is_empty() || word.contains(|c: char| c.is_whitespace() || c == '|' || c == '"') {
+                format!("{:?}", word)
+            } else {
+                word.clone()
+            }
+        })
+        .collect();
+    words.join(" ")
+}
+
+// ソースとアダプタの並びを保持し、1行ずつ評価するセッション
+// イテレータは一度しか読めないので、表示するたびにソースからパイプラインを作り直す
+pub struct Session {
+    registry: Registry,
+    stages: Vec<Vec<String>>,
+    limit: usize
+}
+
+impl Session {
+    pub fn new(registry: Registry) -> Session {
+        Session { registry, stages: Vec::new(), limit: 10 }
+    }
+
+    // 各ステージで表示する値の数
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    pub fn stages(&self) -> &[Vec<String>] {
+        &self.stages
+    }
+
+    fn build(&self, stages: &[Vec<String>]) -> Result<Pipeline, ReplError> {
+        let (source, adapters) = stages.split_first().ok_or(ReplError::NoSource)?;
+        let pipeline = self.registry.build_source(source)?;
+        adapters.iter().try_fold(pipeline, |pipeline, stage| self.registry.apply(pipeline, stage))
+    }
+
+    // i番目までのステージを通した値を、limit個まで1行にして返す
+    fn preview(&self, i: usize) -> Result<String, ReplError> {
+        let mut line = format!("[{}] {} =>", i + 1, display_stage(&self.stages[i]));
+        let mut pipeline = self.build(&self.stages[..=i])?;
+        for (n, item) in pipeline.by_ref().take(self.limit).enumerate() {
+            let sep = if n == 0 { " " } else { ", " };
+            match item {
+                Ok(value) => line.push_str(&format!("{}{}", sep, value)),
+                Err(e) => {
+                    line.push_str(&format!("{}<error: {}>", sep, e));
+                    return Ok(line);
+                }
+            }
+        }
+        if pipeline.next().is_some() {
+            line.push_str(", ...");
+        }
+        Ok(line)
+    }
+
+    fn consume<F>(&self, f: F) -> Result<String, ReplError>
+        where F: FnOnce(&mut dyn Iterator<Item=Item>) -> Result<String, ReplError>
+    {
+        let mut pipeline = self.build(&self.stages)?;
+        f(&mut pipeline)
+    }
+
+    // 1行を評価し、表示する文字列を返す
+    pub fn eval(&mut self, line: &str) -> Result<String, ReplError> {
+        let stages = parse_line(line)?;
+        let first = match stages.first().and_then(|stage| stage.first()) {
+            Some(word) => word.as_str(),
+            None => return Ok(String::new())
+        };
+
+        match (first, stages[0].get(1)) {
+            ("help", _) => return Ok(self.registry.help().trim_end().to_string()),
+            ("show", _) => {
+                let lines = (0..self.stages.len())
+                    .map(|i| self.preview(i))
+                    .collect::<Result<Vec<_>, _>>()?;
+                return Ok(lines.join("\n"));
+            }
+            ("pop", _) => {
+                self.stages.pop().ok_or(ReplError::NoSource)?;
+                return match self.stages.len() {
+                    0 => Ok(String::new()),
+                    n => self.preview(n - 1)
+                };
+            }
+            ("clear", _) => {
+                self.stages.clear();
+                return Ok(String::new());
+            }
+            ("limit", _) => {
+                self.limit = count_argument("limit", &stages[0][1..])?;
+                return Ok(String::new());
+            }
+            ("count", None) => {
+                return self.consume(|items| {
+                    let mut count = 0;
+                    for item in items {
+                        item?;
+                        count += 1;
+                    }
+                    Ok(count.to_string())
+                });
+            }
+            ("sum", None) => {
+                return self.consume(|items| {
+                    let mut sum: i64 = 0;
+                    for item in items {
+                        match item? {
+                            Value::Int(n) => sum = sum.saturating_add(n),
+                            other => return Err(ReplError::Type { function: "sum", value: other })
+                        }
+                    }
+                    Ok(sum.to_string())
+                });
+            }
+            _ => {}
+        }
+
+        // ソースで始まる行は新しいパイプラインを始め、それ以外は今のパイプラインに繋げる
+        let mut next = if self.registry.is_source(first) { Vec::new() } else { self.stages.clone() };
+        let start = next.len();
+        next.extend(stages);
+        // 組み立てられないステージ（未知の名前や引数の誤り）は追加しない
+        let _ = self.build(&next)?;
+
+        self.stages = next;
+        let lines = (start..self.stages.len())
+            .map(|i| self.preview(i))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(lines.join("\n"))
+    }
+}
+
+// inputから1行ずつ読んで評価し、結果をoutに書く。quitまたはexitで終わる
+pub fn run<R, W>(session: &mut Session, input: R, mut out: W, prompt: bool) -> io::Result<()>
+    where R: BufRead,
+          W: Write
+{
+    let mut lines = input.lines();
+    loop {
+        if prompt {
+            write!(out, "> ")?;
+            out.flush()?;
+        }
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => return Ok(())
+        };
+        match line.trim() {
+            "quit" | "exit" => return Ok(()),
+            line => match session.eval(line) {
+                Ok(ref text) if text.is_empty() => {}
+                Ok(text) => writeln!(out, "{}", text)?,
+                Err(e) => writeln!(out, "error: {}", e)?
+            }
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use std::iter::FusedIterator;
+
+// offset, offset + period, offset + 2 * period, ...のtickで発火する周期的なイベント
+// until（そのtickを含まない）とtimes（発火する回数）で終わりを決められる
+#[derive(Debug, Clone)]
+pub struct Periodic<T> {
+    event: T,
+    period: u64,
+    offset: u64,
+    until: Option<u64>,
+    times: Option<u64>
+}
+
+impl<T> Periodic<T> {
+    pub fn every(period: u64, event: T) -> Self {
+        assert!(period > 0, "period must be positive");
+        Periodic { event, period, offset: 0, until: None, times: None }
+    }
+
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn until(mut self, tick: u64) -> Self {
+        self.until = Some(tick);
+        self
+    }
+
+    pub fn times(mut self, times: u64) -> Self {
+        self.times = Some(times);
+        self
+    }
+
+    // tick以降で最初に発火するtick。途中を数えずに計算で求める
+    pub fn next_at(&self, tick: u64) -> Option<u64> {
+        let index = if tick <= self.offset { 0 } else { (tick - self.offset).div_ceil(self.period) };
+        if self.times.is_some_and(|times| index >= times) {
+            return None;
+        }
+        let at = index.checked_mul(self.period)?.checked_add(self.offset)?;
+        if self.until.is_some_and(|until| at >= until) {
+            return None;
+        }
+        Some(at)
+    }
+}
+
+// 1つのtickと、そこで発火したイベント（登録した順）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tick<T> {
+    pub tick: u64,
+    pub events: Vec<T>
+}
+
+// 複数の周期的なイベントをまとめ、tickを1つずつ進めて発火したイベントを返す
+// 全てのイベントが終わるとNoneを返す。終わりのないイベントがあれば無限に続く
+// fired()を使うと、何も発火しないtickを飛ばして次に発火するtickへ直接進む
+#[derive(Debug, Clone)]
+pub struct Schedule<T> {
+    sources: Vec<Periodic<T>>,
+    // u64::MAXのtickを返し終えたらNone
+    tick: Option<u64>
+}
+
+impl<T: Clone> Schedule<T> {
+    pub fn new() -> Self {
+        Schedule { sources: Vec::new(), tick: Some(0) }
+    }
+
+    pub fn with(mut self, source: Periodic<T>) -> Self {
+        self.sources.push(source);
+        self
+    }
+
+    pub fn start_at(mut self, tick: u64) -> Self {
+        self.tick = Some(tick);
+        self
+    }
+
+    // 次にnext()が返すtick。u64::MAXまで進み終えた後はNone
+    pub fn tick(&self) -> Option<u64> {
+        self.tick
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next_fired_at().is_none()
+    }
+
+    // 今のtick以降で最初に何かが発火するtick。イベントの数に比例する時間で求める
+    pub fn next_fired_at(&self) -> Option<u64> {
+        let tick = self.tick?;
+        self.sources.iter().filter_map(|source| source.next_at(tick)).min()
+    }
+
+    fn events_at(&self, tick: u64) -> Vec<T> {
+        self.sources.iter()
+            .filter(|source| source.next_at(tick) == Some(tick))
+            .map(|source| source.event.clone())
+            .collect()
+    }
+
+    // 何も発火しないtickを飛ばし、次に発火するtickとそのイベントを返す
+    pub fn next_fired(&mut self) -> Option<Tick<T>> {
+        self.tick = Some(self.next_fired_at()?);
+        self.next()
+    }
+
+    // 発火したtickだけを返すイテレータ
+    pub fn fired(self) -> Fired<T> {
+        Fired { schedule: self }
+    }
+}
+
+impl<T: Clone> Default for Schedule<T> {
+    fn default() -> Self {
+        Schedule::new()
+    }
+}
+
+impl<T: Clone> Iterator for Schedule<T> {
+    type Item = Tick<T>;
+
+    fn next(&mut self) -> Option<Tick<T>> {
+        if self.is_finished() {
+            return None;
+        }
+        let tick = self.tick?;
+        self.tick = tick.checked_add(1);
+        Some(Tick { tick, events: self.events_at(tick) })
+    }
+
+    // 途中のtickを調べずに進める
+    fn nth(&mut self, n: usize) -> Option<Tick<T>> {
+        self.tick = self.tick.and_then(|tick| tick.checked_add(n as u64));
+        self.next()
+    }
+}
+
+impl<T: Clone> FusedIterator for Schedule<T> {}
+
+#[derive(Debug, Clone)]
+pub struct Fired<T> {
+    schedule: Schedule<T>
+}
+
+impl<T: Clone> Iterator for Fired<T> {
+    type Item = Tick<T>;
+
+    fn next(&mut self) -> Option<Tick<T>> {
+        self.schedule.next_fired()
+    }
+}
+
+impl<T: Clone> FusedIterator for Fired<T> {}
@@ -0,0 +1,101 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+// ヒープの中で順位が最も低いものが先頭に来るよう順序を付けたアイテム
+// キーが等しい場合は先に現れたアイテムを上位とする
+struct Entry<K, T> {
+    key: K,
+    index: usize,
+    item: T,
+    largest: bool
+}
+
+impl<K: Ord, T> Ord for Entry<K, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let by_key = if self.largest { other.key.cmp(&self.key) } else { self.key.cmp(&other.key) };
+        by_key.then(self.index.cmp(&other.index))
+    }
+}
+
+impl<K: Ord, T> PartialOrd for Entry<K, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord, T> PartialEq for Entry<K, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<K: Ord, T> Eq for Entry<K, T> {}
+
+// 大きさkのヒープだけを保持して、上位k個を順位の順に返す
+// 全てをcollect()して並べ替える場合のO(n log n)の時間とO(n)のメモリが、O(n log k)とO(k)で済む
+fn select<I, K, T>(pairs: I, k: usize, largest: bool) -> Vec<(K, T)>
+    where I: Iterator<Item=(K, T)>,
+          K: Ord
+{
+    if k == 0 {
+        return Vec::new();
+    }
+    // kが非常に大きくても確保しすぎないよう、アイテム数の見込みで抑える
+    let mut heap: BinaryHeap<Entry<K, T>> = BinaryHeap::with_capacity(k.min(pairs.size_hint().0));
+    for (index, (key, item)) in pairs.enumerate() {
+        // ヒープが一杯なら、今の最下位よりも上位のものだけを入れる
+        if heap.len() == k {
+            let worst = &heap.peek().unwrap().key;
+            let beats = if largest { key > *worst } else { key < *worst };
+            if !beats {
+                continue;
+            }
+            heap.pop();
+        }
+        heap.push(Entry { key, index, item, largest });
+    }
+    heap.into_sorted_vec().into_iter().map(|e| (e.key, e.item)).collect()
+}
+
+pub trait SelectExt: Iterator + Sized {
+    // 大きい順に最大k個
+    fn top_k(self, k: usize) -> Vec<Self::Item>
+        where Self::Item: Ord
+    {
+        select(self.map(|item| (item, ())), k, true).into_iter().map(|(item, ())| item).collect()
+    }
+
+    // 小さい順に最大k個
+    fn bottom_k(self, k: usize) -> Vec<Self::Item>
+        where Self::Item: Ord
+    {
+        select(self.map(|item| (item, ())), k, false).into_iter().map(|(item, ())| item).collect()
+    }
+
+    // fが返すキーの大きい順に最大k個。キーが等しいものは先に現れた順に並ぶ
+    fn top_k_by_key<K, F>(self, k: usize, mut f: F) -> Vec<Self::Item>
+        where K: Ord,
+              F: FnMut(&Self::Item) -> K
+    {
+        select(self.map(|item| (f(&item), item)), k, true).into_iter().map(|(_, item)| item).collect()
+    }
+
+    // fが返すキーの小さい順に最大k個。キーが等しいものは先に現れた順に並ぶ
+    fn bottom_k_by_key<K, F>(self, k: usize, mut f: F) -> Vec<Self::Item>
+        where K: Ord,
+              F: FnMut(&Self::Item) -> K
+    {
+        select(self.map(|item| (f(&item), item)), k, false).into_iter().map(|(_, item)| item).collect()
+    }
+
+    // 小さい方から数えてk番目（0から数える）のアイテム。アイテムがk個以下ならNone
+    fn kth_smallest(self, k: usize) -> Option<Self::Item>
+        where Self::Item: Ord
+    {
+        let n = k.checked_add(1)?;
+        let mut smallest = self.bottom_k(n);
+        if smallest.len() == n { smallest.pop() } else { None }
+    }
+}
+
+impl<I> SelectExt for I where I: Iterator {}
@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::iter::FusedIterator;
+
+// 整数列を必要になった分だけ生成するイテレータ
+// 値はu64で表すので、primes()以外はu64に収まらなくなったところで終わる
+// take()、take_while()、zip()などと組み合わせて使う
+
+// 素数を小さい順に返す。篩を逐次的に進めるので上限を決めなくてよい
+// compositesには、まだ通過していない奇数の合成数と、それを次に消すときの増分（素数の2倍）を持つ
+#[derive(Debug, Clone)]
+pub struct Primes {
+    candidate: u64,
+    composites: HashMap<u64, u64>
+}
+
+pub fn primes() -> Primes {
+    Primes { candidate: 2, composites: HashMap::new() }
+}
+
+impl Iterator for Primes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if self.candidate == 2 {
+            self.candidate = 3;
+            return Some(2);
+        }
+        loop {
+            let n = self.candidate;
+            self.candidate += 2;
+            match self.composites.remove(&n) {
+                None => {
+                    // 素数pで最初に消すのはp * p
+                    if let Some(square) = n.checked_mul(n) {
+                        self.composites.insert(square, 2 * n);
+                    }
+                    return Some(n);
+                }
+                Some(step) => {
+                    let mut next = n + step;
+                    while self.composites.contains_key(&next) {
+                        next += step;
+                    }
+                    self.composites.insert(next, step);
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl FusedIterator for Primes {}
+
+// 0, 1, 1, 2, 3, 5, ...。u64に収まる最後の値F(93)で終わる
+#[derive(Debug, Clone)]
+pub struct Fibonacci {
+    // 次に返す値の添字nと、F(n)、F(n + 1)
+    n: u64,
+    current: Option<u64>,
+    next: Option<u64>
+}
+
+pub fn fibonacci() -> Fibonacci {
+    Fibonacci { n: 0, current: Some(0), next: Some(1) }
+}
+
+// u64に収まる最後のフィボナッチ数の添字
+const LAST_FIBONACCI: u64 = 93;
+
+// F(n)とF(n + 1)を倍々に求める（fast doubling）
+// F(2k) = F(k) * (2F(k + 1) - F(k))、F(2k + 1) = F(k)^2 + F(k + 1)^2
+fn fibonacci_pair(n: u64) -> (u128, u128) {
+    if n == 0 {
+        return (0, 1);
+    }
+    let (a, b) = fibonacci_pair(n / 2);
+    let even = a * (2 * b - a);
+    let odd = a * a + b * b;
+    if n.is_multiple_of(2) { (even, odd) } else { (odd, even + odd) }
+}
+
+fn fit(x: u128) -> Option<u64> {
+    if x <= u64::MAX as u128 { Some(x as u64) } else { None }
+}
+
+impl Iterator for Fibonacci {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let current = self.current?;
+        self.n += 1;
+        self.current = self.next;
+        self.next = self.next.and_then(|next| current.checked_add(next));
+        Some(current)
+    }
+
+    // 途中を生成せずにO(log n)で求める
+    fn nth(&mut self, n: usize) -> Option<u64> {
+        self.current?;
+        let index = self.n.saturating_add(n as u64);
+        if index > LAST_FIBONACCI {
+            self.current = None;
+            return None;
+        }
+        let (current, next) = fibonacci_pair(index);
+        self.n = index;
+        self.current = fit(current);
+        self.next = fit(next);
+        self.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = if self.current.is_some() { (LAST_FIBONACCI + 1 - self.n) as usize } else { 0 };
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Fibonacci {}
+
+impl FusedIterator for Fibonacci {}
+
+// s角数 P(s, n) = ((s - 2)n^2 - (s - 4)n) / 2 を n = 1, 2, 3, ... について返す
+#[derive(Debug, Clone)]
+pub struct Polygonal {
+    sides: u64,
+    n: u64
+}
+
+// 1, 3, 6, 10, ...
+pub fn triangular() -> Polygonal {
+    polygonal(3)
+}
+
+// 1, 4, 9, 16, ...
+pub fn squares() -> Polygonal {
+    polygonal(4)
+}
+
+// 1, 5, 12, 22, ...
+pub fn pentagonal() -> Polygonal {
+    polygonal(5)
+}
+
+pub fn polygonal(sides: u64) -> Polygonal {
+    assert!(sides >= 3, "a polygon needs at least 3 sides: {}", sides);
+    Polygonal { sides, n: 1 }
+}
+
+impl Polygonal {
+    // n((s - 2)(n - 1) + 2) / 2と変形すると、途中で負にならない
+    fn value(&self, n: u64) -> Option<u64> {
+        let (n, s) = (n as u128, self.sides as u128);
+        let value = (s - 2).checked_mul(n - 1)?.checked_add(2)?.checked_mul(n)? / 2;
+        fit(value)
+    }
+}
+
+impl Iterator for Polygonal {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let value = self.value(self.n);
+        self.n = if value.is_some() { self.n + 1 } else { u64::MAX };
+        value
+    }
+
+    // 一般項の式から直接求める
+    fn nth(&mut self, n: usize) -> Option<u64> {
+        self.n = self.n.saturating_add(n as u64);
+        self.next()
+    }
+}
+
+impl FusedIterator for Polygonal {}
+
+// startから始め、偶数なら2で割り、奇数なら3倍して1を足すことを1になるまで繰り返す
+// 途中でu64に収まらなくなった場合もそこで終わる
+#[derive(Debug, Clone)]
+pub struct Collatz {
+    next: Option<u64>
+}
+
+pub fn collatz(start: u64) -> Collatz {
+    assert!(start > 0, "the Collatz sequence starts from a positive number");
+    Collatz { next: Some(start) }
+}
+
+impl Iterator for Collatz {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let n = self.next?;
+        self.next = match n {
+            1 => None,
+            n if n.is_multiple_of(2) => Some(n / 2),
+            n => n.checked_mul(3).and_then(|m| m.checked_add(1))
+        };
+        Some(n)
+    }
+}
+
+impl FusedIterator for Collatz {}
+
+// 0!, 1!, 2!, ...。u64に収まる最後の値20!で終わる
+#[derive(Debug, Clone)]
+pub struct Factorials {
+    n: u64,
+    current: Option<u64>
+}
+
+pub fn factorials() -> Factorials {
+    Factorials { n: 0, current: Some(1) }
+}
+
+impl Iterator for Factorials {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let current = self.current?;
+        self.n += 1;
+        self.current = current.checked_mul(self.n);
+        Some(current)
+    }
+
+    // 途中の値を返さずに掛け合わせる
+    fn nth(&mut self, n: usize) -> Option<u64> {
+        let current = self.current?;
+        let target = self.n.saturating_add(n as u64);
+        self.current = (self.n + 1..=target).try_fold(current, |product, k| product.checked_mul(k));
+        self.n = target;
+        self.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = if self.current.is_some() { (LAST_FACTORIAL + 1 - self.n) as usize } else { 0 };
+        (remaining, Some(remaining))
+    }
+}
+
+// u64に収まる最後の階乗
+const LAST_FACTORIAL: u64 = 20;
+
+impl ExactSizeIterator for Factorials {}
+
+impl FusedIterator for Factorials {}
@@ -0,0 +1,227 @@
+// 級数の和を求めるアダプタ
+// partial_sums()などで部分和の列にしてから、aitken()やrichardson()で収束を速める
+// 交代級数ならeuler_transform()に項の列を渡すこともできる
+
+// 部分和 s_n = t_0 + ... + t_n を順に返す
+#[derive(Debug, Clone)]
+pub struct PartialSums<I> {
+    iter: I,
+    sum: f64
+}
+
+impl<I> Iterator for PartialSums<I>
+    where I: Iterator<Item=f64>
+{
+    type Item = f64;
+
+    fn next(&mut self) -> Option<f64> {
+        self.sum += self.iter.next()?;
+        Some(self.sum)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+// Neumaierの方法で丸め誤差を補正した部分和を返す
+#[derive(Debug, Clone)]
+pub struct CompensatedSums<I> {
+    iter: I,
+    sum: f64,
+    compensation: f64
+}
+
+impl<I> Iterator for CompensatedSums<I>
+    where I: Iterator<Item=f64>
+{
+    type Item = f64;
+
+    fn next(&mut self) -> Option<f64> {
+        let x = self.iter.next()?;
+        neumaier_add(&mut self.sum, &mut self.compensation, x);
+        Some(self.sum + self.compensation)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+// sumにxを足し、失われた下位の桁をcompensationに貯める
+// Kahanの方法と違い、xの方が絶対値が大きい場合も補正できる
+fn neumaier_add(sum: &mut f64, compensation: &mut f64, x: f64) {
+    let t = *sum + x;
+    if sum.abs() >= x.abs() {
+        *compensation += (*sum - t) + x;
+    } else {
+        *compensation += (x - t) + *sum;
+    }
+    *sum = t;
+}
+
+// Aitkenのδ²法。部分和の列 s_n, s_n+1, s_n+2 から
+// s_n+2 - (s_n+2 - s_n+1)^2 / (s_n+2 - 2s_n+1 + s_n) を返す
+// 誤差が等比数列的に減る列（交代級数など）で特に効果がある
+#[derive(Debug, Clone)]
+pub struct Aitken<I> {
+    iter: I,
+    window: Option<(f64, f64)>
+}
+
+impl<I> Iterator for Aitken<I>
+    where I: Iterator<Item=f64>
+{
+    type Item = f64;
+
+    fn next(&mut self) -> Option<f64> {
+        let (s0, s1) = match self.window {
+            Some(window) => window,
+            None => (self.iter.next()?, self.iter.next()?)
+        };
+        let s2 = self.iter.next()?;
+        self.window = Some((s1, s2));
+
+        let denominator = s2 - 2.0 * s1 + s0;
+        if denominator == 0.0 {
+            Some(s2)
+        } else {
+            Some(s2 - (s2 - s1) * (s2 - s1) / denominator)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let pending = if self.window.is_some() { 0 } else { 2 };
+        let (lower, upper) = self.iter.size_hint();
+        (lower.saturating_sub(pending), upper.map(|n| n.saturating_sub(pending)))
+    }
+}
+
+// 交代級数 Σ(-1)^n a_n の項 t_n = (-1)^n a_n を受け取り、Euler変換した級数
+// Σ(-1)^k Δ^k a_0 / 2^(k+1) の部分和を返す（Δは前進差分）
+// 差分表の最後の斜めの列だけを持ち、項が1つ増えるたびに更新する
+#[derive(Debug, Clone)]
+pub struct EulerTransform<I> {
+    iter: I,
+    // diagonal[k] = Δ^k a_(n-k)
+    diagonal: Vec<f64>,
+    sum: f64
+}
+
+impl<I> Iterator for EulerTransform<I>
+    where I: Iterator<Item=f64>
+{
+    type Item = f64;
+
+    fn next(&mut self) -> Option<f64> {
+        let term = self.iter.next()?;
+        let n = self.diagonal.len();
+        let sign = if n.is_multiple_of(2) { 1.0 } else { -1.0 };
+        let a = sign * term;
+
+        let mut next = Vec::with_capacity(n + 1);
+        next.push(a);
+        for k in 0..n {
+            next.push(next[k] - self.diagonal[k]);
+        }
+        self.diagonal = next;
+
+        // 新しく求まったΔ^n a_0を足す
+        self.sum += sign * self.diagonal[n] / 2f64.powi(n as i32 + 1);
+        Some(self.sum)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+// Richardson補外。誤差がc / n^orderに比例して減る部分和の列 s_1, s_2, ... から
+// ((n + 1)^order s_n+1 - n^order s_n) / ((n + 1)^order - n^order) を返し、主要な誤差の項を消す
+// 交代級数のように誤差の符号が入れ替わる列には向かない（そちらはaitken()を使う）
+#[derive(Debug, Clone)]
+pub struct Richardson<I> {
+    iter: I,
+    order: i32,
+    n: u64,
+    previous: Option<f64>
+}
+
+impl<I> Iterator for Richardson<I>
+    where I: Iterator<Item=f64>
+{
+    type Item = f64;
+
+    fn next(&mut self) -> Option<f64> {
+        let previous = match self.previous {
+            Some(previous) => previous,
+            None => self.iter.next()?
+        };
+        let current = self.iter.next()?;
+        self.previous = Some(current);
+
+        let lower = (self.n as f64).powi(self.order);
+        let upper = ((self.n + 1) as f64).powi(self.order);
+        self.n += 1;
+        Some((upper * current - lower * previous) / (upper - lower))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let pending = if self.previous.is_some() { 0 } else { 1 };
+        let (lower, upper) = self.iter.size_hint();
+        (lower.saturating_sub(pending), upper.map(|n| n.saturating_sub(pending)))
+    }
+}
+
+pub trait SeriesExt: Iterator<Item=f64> + Sized {
+    // 項の列を部分和の列にする
+    fn partial_sums(self) -> PartialSums<Self> {
+        PartialSums { iter: self, sum: 0.0 }
+    }
+
+    // partial_sums()と同じだが、丸め誤差を補正する
+    fn compensated_sums(self) -> CompensatedSums<Self> {
+        CompensatedSums { iter: self, sum: 0.0, compensation: 0.0 }
+    }
+
+    // 部分和の列に適用する
+    fn aitken(self) -> Aitken<Self> {
+        Aitken { iter: self, window: None }
+    }
+
+    // 交代級数の項の列に適用する
+    fn euler_transform(self) -> EulerTransform<Self> {
+        EulerTransform { iter: self, diagonal: Vec::new(), sum: 0.0 }
+    }
+
+    // 1から数えたn番目の部分和の列に適用する。orderは正でなければならない
+    fn richardson(self, order: i32) -> Richardson<Self> {
+        assert!(order > 0, "order must be positive");
+        Richardson { iter: self, order, n: 1, previous: None }
+    }
+
+    // Kahanの補正付き総和
+    fn kahan_sum(self) -> f64 {
+        let mut sum = 0.0;
+        let mut compensation = 0.0;
+        for x in self {
+            let y = x - compensation;
+            let t = sum + y;
+            compensation = (t - sum) - y;
+            sum = t;
+        }
+        sum
+    }
+
+    // Neumaierの補正付き総和
+    fn neumaier_sum(self) -> f64 {
+        let mut sum = 0.0;
+        let mut compensation = 0.0;
+        for x in self {
+            neumaier_add(&mut sum, &mut compensation, x);
+        }
+        sum + compensation
+    }
+}
+
+impl<I> SeriesExt for I where I: Iterator<Item=f64> {}
@@ -0,0 +1,155 @@
+use std::iter::FromIterator;
+
+// 1回の走査で件数、平均、分散、歪度、最小、最大をまとめて求める
+// 平均と分散はWelfordの方法で逐次更新するので、値が大きく分散が小さい場合でも
+// 二乗和から引き算する方法のように桁落ちしない
+// 値が1つもない場合、各統計量はNoneを返す
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    count: u64,
+    mean: f64,
+    // 平均からの偏差の2乗和と3乗和
+    m2: f64,
+    m3: f64,
+    min: f64,
+    max: f64
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Stats { count: 0, mean: 0.0, m2: 0.0, m3: 0.0, min: f64::INFINITY, max: f64::NEG_INFINITY }
+    }
+
+    pub fn push(&mut self, x: f64) {
+        let n1 = self.count as f64;
+        self.count += 1;
+        let n = self.count as f64;
+
+        let delta = x - self.mean;
+        let delta_n = delta / n;
+        let term = delta * delta_n * n1;
+        self.mean += delta_n;
+        self.m3 += term * delta_n * (n - 2.0) - 3.0 * delta_n * self.m2;
+        self.m2 += term;
+
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+    }
+
+    // 別々に集計した結果をまとめる（チャンクごとに集計してから合わせる場合など）
+    pub fn merge(&mut self, other: &Stats) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = *other;
+            return;
+        }
+
+        let (na, nb) = (self.count as f64, other.count as f64);
+        let n = na + nb;
+        let delta = other.mean - self.mean;
+
+        let m2 = self.m2 + other.m2 + delta * delta * na * nb / n;
+        let m3 = self.m3 + other.m3
+            + delta * delta * delta * na * nb * (na - nb) / (n * n)
+            + 3.0 * delta * (na * other.m2 - nb * self.m2) / n;
+
+        self.mean += delta * nb / n;
+        self.m2 = m2;
+        self.m3 = m3;
+        self.count += other.count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        self.nonempty(self.mean)
+    }
+
+    // 母分散（nで割る）
+    pub fn variance(&self) -> Option<f64> {
+        self.nonempty(self.m2 / self.count as f64)
+    }
+
+    // 標本分散（n - 1で割る）。値が2つ以上必要
+    pub fn sample_variance(&self) -> Option<f64> {
+        if self.count < 2 {
+            None
+        } else {
+            Some(self.m2 / (self.count - 1) as f64)
+        }
+    }
+
+    pub fn std_dev(&self) -> Option<f64> {
+        self.variance().map(f64::sqrt)
+    }
+
+    pub fn sample_std_dev(&self) -> Option<f64> {
+        self.sample_variance().map(f64::sqrt)
+    }
+
+    // 母歪度。全ての値が等しいと定義できないのでNone
+    pub fn skewness(&self) -> Option<f64> {
+        if self.count == 0 || self.m2 == 0.0 {
+            None
+        } else {
+            let n = self.count as f64;
+            Some(n.sqrt() * self.m3 / self.m2.powf(1.5))
+        }
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        self.nonempty(self.min)
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        self.nonempty(self.max)
+    }
+
+    fn nonempty(&self, value: f64) -> Option<f64> {
+        if self.count == 0 { None } else { Some(value) }
+    }
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Stats::new()
+    }
+}
+
+impl Extend<f64> for Stats {
+    fn extend<T: IntoIterator<Item=f64>>(&mut self, iter: T) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+impl<'a> Extend<&'a f64> for Stats {
+    fn extend<T: IntoIterator<Item=&'a f64>>(&mut self, iter: T) {
+        self.extend(iter.into_iter().cloned());
+    }
+}
+
+impl FromIterator<f64> for Stats {
+    fn from_iter<T: IntoIterator<Item=f64>>(iter: T) -> Self {
+        let mut stats = Stats::new();
+        stats.extend(iter);
+        stats
+    }
+}
+
+impl<'a> FromIterator<&'a f64> for Stats {
+    fn from_iter<T: IntoIterator<Item=&'a f64>>(iter: T) -> Self {
+        iter.into_iter().cloned().collect()
+    }
+}
@@ -0,0 +1,83 @@
+#[derive(Debug, Clone, PartialEq)]
+pub enum BinaryTree<T> {
+    Empty,
+    NonEmpty(Box<TreeNode<T>>)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeNode<T> {
+    pub element: T,
+    pub left: BinaryTree<T>,
+    pub right: BinaryTree<T>
+}
+
+pub struct TreeIter<'a, T: 'a> {
+    unvisited: Vec<&'a TreeNode<T>>
+}
+
+impl <'a, T: 'a> TreeIter<'a, T> {
+    fn push_left_edge(&mut self, mut tree: &'a BinaryTree<T>) {
+        while let BinaryTree::NonEmpty(ref node) = *tree {
+            self.unvisited.push(node);
+            tree = &node.left;
+        }
+    }
+}
+
+impl<T> BinaryTree<T> {
+    pub fn iter(&self) -> TreeIter<'_, T> {
+        let mut iter = TreeIter { unvisited: Vec::new() };
+        iter.push_left_edge(self);
+        iter
+    }
+}
+
+impl<T: Ord> BinaryTree<T> {
+    // 二分探索木として、値の大小に従って左右どちらかの葉に追加する
+    pub fn add(&mut self, value: T) {
+        match *self {
+            BinaryTree::Empty => {
+                *self = make_node(BinaryTree::Empty, value, BinaryTree::Empty);
+            }
+            BinaryTree::NonEmpty(ref mut node) => {
+                if value <= node.element {
+                    node.left.add(value);
+                } else {
+                    node.right.add(value);
+                }
+            }
+        }
+    }
+}
+
+impl<'a, T: 'a> IntoIterator for &'a BinaryTree<T> {
+    type Item = &'a T;
+    type IntoIter = TreeIter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> Iterator for TreeIter<'a, T> {
+    type Item = &'a T;
+
+    // 書籍どおり、?を使わずにmatchで書いている
+    #[allow(clippy::question_mark)]
+    fn next(&mut self) -> Option<&'a T> {
+        // このイテレーションが生成するべきノードを見つける。無ければNoneを返してイテレーションを終了。
+        let node = match self.unvisited.pop() {
+            None => return None,
+            Some(n) => n
+        };
+
+        // このノードの次のノードは、右側にある子ノードのうち最も左のノードなので、そこまでスタックにpushする。
+        self.push_left_edge(&node.right);
+
+        // 見付けたノードへの参照を生成して返す。
+        Some(&node.element)
+    }
+}
+
+pub fn make_node<T>(left: BinaryTree<T>, element: T, right: BinaryTree<T>) -> BinaryTree<T> {
+    BinaryTree::NonEmpty(Box::new(TreeNode { left, element, right }))
+}
@@ -0,0 +1,228 @@
+// 書籍の説明どおりの書き方（あえて冗長なものを含む）を残しているテストでは、それに対するlintだけを許可する
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use rust_iterator_study::fizzbuzz::fizz_buzz;
+use rust_iterator_study::number::parse_number;
+
+#[test]
+fn map_and_filter() {
+    // text.lines()で返された各行のイテレータをmap()アダプタで空白を除去する
+    let text = "  ponies  \n  giraffers\niguanas  \nsquid".to_string();
+    let v: Vec<&str> = text.lines()
+        .map(str::trim)
+        .collect();
+    assert_eq!(v, ["ponies", "giraffers", "iguanas", "squid"]);
+
+    // さらにfilter()アダプタでiguanasだけ取り除く
+    let v2: Vec<&str> = text.lines()
+        .map(str::trim)
+        .filter(|s| *s != "iguanas") // trueを返すものだけをイテレータのアイテムとして生成する
+        .collect();
+    assert_eq!(v2, ["ponies", "giraffers", "squid"]);
+}
+
+#[test]
+fn adapters_are_lazy() {
+    // iter()呼び出しだけでは値が要求されずnext()が呼ばれた時に初めて使われる
+    let mut calls = 0;
+    {
+        let mut lazy = ["earth", "water", "air", "fire"]
+            .iter().map(|_| calls += 1);
+        lazy.next();
+    }
+    assert_eq!(calls, 1);
+}
+
+#[test]
+#[allow(clippy::redundant_closure, clippy::result_filter_map)]
+fn filter_map_drops_unparsable_words() {
+    let text = "1\nfrond .25 289\n3.1415 estuary\n";
+    // 1) ホワイトスペースで区切られたスライスを
+    // 2) f64::from_str()でパースを試みる -> Result<f64, ParseFloatError>が返る
+    // 3) ok()を実行すると、エラーの場合はNoneとなりドロップされ処理は継続しない
+    // 4) パース成功したものはSome(v)のvを取り出す
+    let roots: Vec<f64> = text.split_whitespace()
+        .filter_map(|w| f64::from_str(w).ok())
+        .map(f64::sqrt)
+        .collect();
+
+    // 上のfilter_map()と同じ処理ををmap()->filter()->map()で書き直したもの
+    let roots2: Vec<f64> = text.split_whitespace()
+        .map(|w| f64::from_str(w))
+        .filter(|r| r.is_ok())
+        .map(|r| r.unwrap())
+        .map(f64::sqrt)
+        .collect();
+
+    assert_eq!(roots, roots2);
+    assert_eq!(roots[..3], [1.0, 0.5, 17.0]);
+}
+
+#[test]
+fn flat_map_concatenates_inner_iterators() {
+    let mut major_cities = HashMap::new();
+    major_cities.insert("Japan", vec!["Tokyo", "Kyoto"]);
+    major_cities.insert("The United States", vec!["Portland", "Nashville"]);
+    major_cities.insert("Brazil", vec!["Sao Paulo", "Brasilia"]);
+    major_cities.insert("Kenya", vec!["Nairobi", "Mombasa"]);
+    major_cities.insert("The Netherlands", vec!["Amsterdam", "Utrecht"]);
+
+    let countries = ["Japan", "Brazil", "Kenya"];
+
+    let cities: Vec<&str> = countries.iter()
+        .flat_map(|country| &major_cities[country])
+        .cloned()
+        .collect();
+    assert_eq!(cities, ["Tokyo", "Kyoto", "Sao Paulo", "Brasilia", "Nairobi", "Mombasa"]);
+}
+
+#[test]
+fn scan_stops_when_closure_returns_none() {
+    let iter = (0..10)
+        .scan(0, |sum, item| {
+            // イテレータの入力0, 1, 2, 3, 4...の2乗が10を超えるまで継続される
+            // 4*4 = 16になったところでNoneが返却されscan()アダプタは処理を中止する
+            *sum += item;
+            if *sum > 10 {
+                None
+            } else {
+                Some(item * item)
+            }
+        });
+
+    assert_eq!(iter.collect::<Vec<i32>>(), vec![0, 1, 4, 9, 16]);
+}
+
+const MESSAGE: &str = "To: jimb\n\
+                       From: superego <editor@oreilly.com>\n\
+                       \n\
+                       Did you get any writing done today?\n\
+                       When will you stop wasting time plotting fractals?\n";
+
+#[test]
+fn take_while_and_skip_while_split_at_blank_line() {
+    // take_while()は引数predicateがfalseになったらNoneを生成してイテレートを中止する
+    let headers: Vec<&str> = MESSAGE.lines().take_while(|l| !l.is_empty()).collect();
+    assert_eq!(headers, ["To: jimb", "From: superego <editor@oreilly.com>"]);
+
+    // skip_while()は引数predicateがtrueのアイテムだけをスキップして繰り返す
+    let body: Vec<&str> = MESSAGE.lines().skip_while(|l| !l.is_empty()).collect();
+    assert_eq!(body, ["",
+                      "Did you get any writing done today?",
+                      "When will you stop wasting time plotting fractals?"]);
+}
+
+#[test]
+fn by_ref_lets_the_iterator_be_reused() {
+    let mut lines = MESSAGE.lines();
+    // by_ref()呼び出しをするとイテレータの可変参照を借用する
+    let headers: Vec<&str> = lines.by_ref().take_while(|l| !l.is_empty()).collect();
+    assert_eq!(headers.len(), 2);
+
+    // 上では参照を渡しているだけなので、残りをもう一度利用できる
+    // take_while()は空行を消費しているので、本文の先頭から続く
+    let body: Vec<&str> = lines.collect();
+    assert_eq!(body, ["Did you get any writing done today?",
+                      "When will you stop wasting time plotting fractals?"]);
+}
+
+#[test]
+fn peekable_parse_number_leaves_the_separator() {
+    let mut chars = "226153980,1766319049".chars().peekable();
+
+    assert_eq!(parse_number(&mut chars), 226153980);
+
+    // parse_number()の内部実装がカンマを取り出さないため、ここでnext()を呼んで消費する
+    assert_eq!(chars.next(), Some(','));
+
+    assert_eq!(parse_number(&mut chars), 1766319049);
+
+    // 取り出すものが無くなったらNoneが返される
+    assert_eq!(chars.next(), None);
+}
+
+#[test]
+fn inspect_passes_items_through() {
+    let mut before = String::new();
+    let upper_case: String = "groβe".chars()
+        .inspect(|c| before.push(*c))
+        .flat_map(|c| c.to_uppercase()) // アイテムをそのまま通過させるため、デバッグ出力などに使われる
+        .collect();
+    assert_eq!(upper_case, "GROΒE");
+    assert_eq!(before, "groβe");
+}
+
+#[test]
+fn chain_joins_two_iterators() {
+    // 1つ目のイテレータから2つ目のイテレータを繋げる
+    let v: Vec<i32> = (1..4).chain(vec![20, 30, 40]).collect();
+    assert_eq!(v, [1, 2, 3, 20, 30, 40]);
+
+    // 1つ目のイテレータから2つ目のイテレータを繋げたものをrev()で逆順にする
+    let rev_v: Vec<i32> = (1..4).chain(vec![20, 30, 40]).rev().collect();
+    assert_eq!(rev_v, [40, 30, 20, 3, 2, 1]);
+}
+
+#[test]
+fn enumerate_pairs_items_with_indices() {
+    let bands = vec![10, 20, 30, 40];
+    // enumerate()は(0, 10), (1, 20), (2, 30), (3, 40)と
+    // インデックスとアイテムのペアになったイテレータを生成する
+    let products: Vec<usize> = bands.into_iter().enumerate()
+        .map(|(i, band)| i * band)
+        .collect();
+    assert_eq!(products, [0, 20, 60, 120]);
+}
+
+#[test]
+fn zip_stops_at_the_shorter_iterator() {
+    // zip()アダプタは2つのイテレータを合わせて1つのイテレータにする
+    // 閉じ合わせるどちらかのイテレータが終了した時点でzip()アダプタも終了する
+    // ここでは"ABCD".chars()が終了した時点で(0..)も繰り返しを終了している
+    let v: Vec<_> = (0..).zip("ABCD".chars()).collect();
+    assert_eq!(v, vec![(0, 'A'), (1, 'B'), (2, 'C'), (3, 'D')]);
+
+    // zip()の引数はイテレータそのものでなくイテレート可能なものなら何でもよい
+    use std::iter::repeat;
+    let endings = vec!["once", "twice", "chikien soup with rice"];
+    let rhyme: Vec<_> = repeat("going")
+        .zip(endings)
+        .collect();
+    assert_eq!(rhyme, vec![("going", "once"),
+                           ("going", "twice"),
+                           ("going", "chikien soup with rice")]);
+}
+
+#[test]
+#[allow(clippy::iter_next_slice)]
+fn cloned_yields_values_instead_of_references() {
+    // cloned()はCloneを実装する型のイテレータから値をクローンして生成するイテレータを返す
+    let a = ['1', '2', '3', '∞'];
+
+    assert_eq!(a.iter().next(),          Some(&'1'));
+    assert_eq!(a.iter().cloned().next(), Some('1'));
+}
+
+#[test]
+fn cycle_repeats_forever() {
+    let dirs = ["North", "East", "South", "West"];
+
+    // cycleアダプタは、元となるイテレータが生成するアイテムを無限に繰り返すイテレータを返す
+    // 呼ばれるイテレータのアイテムはCloneを実装する型でなければならない
+    let mut spin = dirs.iter().cycle();
+    assert_eq!(spin.next(), Some(&"North"));
+    assert_eq!(spin.next(), Some(&"East"));
+    assert_eq!(spin.next(), Some(&"South"));
+    assert_eq!(spin.next(), Some(&"West"));
+    assert_eq!(spin.next(), Some(&"North"));
+    assert_eq!(spin.next(), Some(&"East"));
+}
+
+#[test]
+fn fizz_buzz_zips_two_cycles() {
+    let lines: Vec<String> = fizz_buzz().take(15).collect();
+    assert_eq!(lines, ["1", "2", "fizz", "4", "buzz", "fizz", "7", "8", "fizz", "buzz",
+                       "11", "fizz", "13", "14", "fizzbuzz"]);
+}
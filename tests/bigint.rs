@@ -0,0 +1,87 @@
+use rust_iterator_study::arith::{big_factorial, big_triangle, factorial, triangle};
+use rust_iterator_study::bigint::{BigInt, BigUint, ParseBigIntError};
+
+const FACTORIAL_100: &str = "93326215443944152681699238856266700490715968264381621468592963895217599993229915608941463976156518286253697920827223758251185210916864000000000000000000000000";
+
+fn samples() -> Vec<u64> {
+    let mut samples = vec![0, 1, 2, 999_999_999, 1_000_000_000, u32::MAX as u64, u32::MAX as u64 + 1, u64::MAX];
+    samples.extend((1..50u64).map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> (i % 64)));
+    samples
+}
+
+#[test]
+fn factorial_and_triangle_match_the_u64_versions() {
+    for n in 0..=20 {
+        assert_eq!(big_factorial(n), BigUint::from(factorial(n)));
+        assert_eq!(big_triangle(n), BigUint::from(triangle(n)));
+    }
+    assert_eq!(big_factorial(100).to_string(), FACTORIAL_100);
+    assert_eq!(big_factorial(100).bits(), 525);
+
+    // 和や積は値でも参照でも求められる
+    let numbers: Vec<BigUint> = (1..=100u32).map(BigUint::from).collect();
+    assert_eq!(numbers.iter().product::<BigUint>(), big_factorial(100));
+    assert_eq!(numbers.into_iter().sum::<BigUint>(), BigUint::from(5_050u32));
+}
+
+#[test]
+fn arithmetic_matches_u128() {
+    for &a in &samples() {
+        for &b in &samples() {
+            let (x, y) = (BigUint::from(a), BigUint::from(b));
+            assert_eq!(&x + &y, BigUint::from(a as u128 + b as u128));
+            assert_eq!(&x * &y, BigUint::from(a as u128 * b as u128));
+            assert_eq!(x.checked_sub(&y), (a as u128).checked_sub(b as u128).map(BigUint::from));
+            assert_eq!(x.cmp(&y), a.cmp(&b));
+        }
+    }
+}
+
+#[test]
+fn signed_arithmetic_matches_i128() {
+    let samples: Vec<i64> = samples().into_iter().map(|x| (x >> 1) as i64).flat_map(|x| vec![x, -x]).collect();
+    for &a in &samples {
+        for &b in &samples {
+            let (x, y) = (BigInt::from(a), BigInt::from(b));
+            assert_eq!(&x + &y, BigInt::from(a as i128 + b as i128));
+            assert_eq!(&x - &y, BigInt::from(a as i128 - b as i128));
+            assert_eq!(&x * &y, BigInt::from(a as i128 * b as i128));
+            assert_eq!(x.cmp(&y), a.cmp(&b));
+        }
+    }
+    assert_eq!(BigInt::from(i128::MIN).to_string(), i128::MIN.to_string());
+    assert_eq!(-BigInt::from(0), BigInt::zero());
+    assert!(!(-BigInt::zero()).is_negative());
+}
+
+#[test]
+fn display_and_from_str_round_trip() {
+    for text in &["0", "7", "1000000000", "1000000001", "18446744073709551616", FACTORIAL_100] {
+        assert_eq!(text.parse::<BigUint>().unwrap().to_string(), *text);
+        assert_eq!(format!("-{}", text).parse::<BigInt>().unwrap().to_string(),
+                   if *text == "0" { "0".to_string() } else { format!("-{}", text) });
+    }
+    assert_eq!("+42".parse::<BigInt>(), Ok(BigInt::from(42)));
+    assert_eq!("000123".parse::<BigUint>(), Ok(BigUint::from(123u8)));
+    assert_eq!(format!("{:>6}|{:+}", BigUint::from(42u8), BigInt::from(7)), "    42|+7");
+
+    for text in &["", "-", "12a", " 1", "-5", "--5", "-+5"] {
+        assert_eq!(text.parse::<BigUint>(), Err(ParseBigIntError { input: text.to_string() }));
+    }
+    assert_eq!("-+5".parse::<BigInt>().unwrap_err().to_string(), "invalid integer: \"-+5\"");
+}
+
+#[test]
+#[should_panic(expected = "attempt to subtract with overflow")]
+fn unsigned_subtraction_below_zero_panics() {
+    let _ = BigUint::from(1u8) - BigUint::from(2u8);
+}
+
+#[test]
+fn signed_sum_and_product() {
+    let product: BigInt = (1..=30).map(|i| BigInt::from(if i % 2 == 0 { i } else { -i })).product();
+    // 奇数の15個が負なので積も負になる
+    assert_eq!(product.to_string(), format!("-{}", big_factorial(30)));
+    let sum: BigInt = (-1000..=999).map(BigInt::from).sum();
+    assert_eq!(sum, BigInt::from(-1000));
+}
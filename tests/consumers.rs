@@ -0,0 +1,175 @@
+// 書籍の説明どおりの書き方（あえて冗長なものを含む）を残しているテストでは、それに対するlintだけを許可する
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use rust_iterator_study::arith::{factorial, triangle};
+
+fn populations() -> HashMap<&'static str, i32> {
+    let mut populations = HashMap::new();
+    populations.insert("Portland",  583_776);
+    populations.insert("Fossil",        449);
+    populations.insert("Greenhorn",       2);
+    populations.insert("Bording",     7_762);
+    populations.insert("The Dalles", 15_340);
+    populations
+}
+
+#[test]
+fn sum_and_product() {
+    assert_eq!(triangle(20), 210);
+    assert_eq!(factorial(20), 2432902008176640000);
+
+    // forループで足し合わせても同じ結果になる
+    let mut sum = 0;
+    for i in 1..21 {
+        sum += i;
+    }
+    assert_eq!(triangle(20), sum);
+}
+
+#[test]
+fn max_and_min() {
+    // イテレータが生成するアイテムの最大を返す
+    assert_eq!([-2, 0, 1, 0, -2, -5].iter().max(), Some(&1));
+    // イテレータが生成するアイテムの最小を返す
+    assert_eq!([-2, 0, 1, 0, -2, -5].iter().min(), Some(&-5));
+}
+
+#[test]
+fn max_by_and_min_by_with_a_comparison_function() {
+    // max_by()/min_by()に渡す比較用関数
+    // NaNが与えられるとpanicが起きるコードになっている
+    fn cmp(lhs: &&f64, rhs: &&f64) -> Ordering {
+        lhs.partial_cmp(rhs).unwrap()
+    }
+
+    // 変数numbersの中にstd::f64::NANが含まれていると実行時にpanicが起きる
+    let numbers = [1.0, 4.0, 2.0];
+    assert_eq!(numbers.iter().max_by(cmp), Some(&4.0));
+    assert_eq!(numbers.iter().min_by(cmp), Some(&1.0));
+}
+
+#[test]
+fn max_by_key_and_min_by_key() {
+    let populations = populations();
+
+    // max_by_key()/min_by_key()では各アイテムに引数クロージャを適用した結果のうち
+    // 最大/最小のものを返す
+    assert_eq!(populations.iter().max_by_key(|&(_name, pop)|pop),
+               Some((&"Portland", &583_776)));
+    assert_eq!(populations.iter().min_by_key(|&(_name, pop)|pop),
+               Some((&"Greenhorn", &2)));
+}
+
+#[test]
+fn comparing_item_sequences() {
+    let packed =  "Helen of Troy";
+    let spaced =  "Helen   of   Troy";
+    let obscure = "Helen of Sandusky";
+
+    assert!(packed != spaced);
+    // 空白区切りの単語イテレータ同士で比較すると真になる
+    assert!(packed.split_whitespace().eq(spaced.split_whitespace()));
+
+    assert!(spaced < obscure);
+    // 'Troy' > 'Sandusky' の比較結果となるため真になる
+    assert!(spaced.split_whitespace().gt(obscure.split_whitespace()));
+}
+
+#[test]
+fn any_and_all() {
+    let id = "Iterator";
+
+    // chars()で取り出したアイテムのどれかがis_uppercase()にtrueを返したら繰り返しを中止
+    assert!( id.chars().any(char::is_uppercase));
+    // chars()で取り出したアイテムのすべてがis_uppercase()にtrueを返す時だけtrueとなる
+    assert!(!id.chars().all(char::is_uppercase));
+}
+
+#[test]
+fn position_and_rposition() {
+    let text = "Xerxes";
+    assert_eq!(text.chars().position(|c| c == 'e'), Some(1));
+    assert_eq!(text.chars().position(|c| c == 'z'), None);
+
+    // rposition()はサイズが決定しているイテレータでなければ使えない
+    // &strに対するchar()イテレータは事前にサイズが決定しないためバイト配列のように配列長がわかるもので使う
+    let bytes = b"Xerxes";
+    assert_eq!(bytes.iter().rposition(|&c| c == b'e'), Some(4));
+    assert_eq!(bytes.iter().rposition(|&c| c == b'X'), Some(0));
+}
+
+#[test]
+#[allow(clippy::legacy_numeric_constants, clippy::unnecessary_fold)]
+fn fold_generalizes_other_consumers() {
+    // foldメソッドのシグネチャは以下で、値の初期値（init）とクロージャ（f）を引数に渡す
+    // fn fold<A, F>(self, init: A, f: F) -> A
+    let a = [5, 6, 7, 8, 9, 10];
+
+    assert_eq!(a.iter().fold(0, |n, _| n+1), 6);      // like count()
+    assert_eq!(a.iter().fold(0, |n, i| n+i), 45);     // like sum()
+    assert_eq!(a.iter().fold(1, |n, i| n*i), 151200); // like product()
+
+    // like max()
+    assert_eq!(a.iter().fold(i32::min_value(), |m, &i| std::cmp::max(m, i)),
+              10);
+
+    let a = ["Pack ", "my ", "box ", "with ",
+             "five ", "dozen ", "liquor ", "jugs"];
+    let pangram = a.iter().fold(String::new(),
+                               |mut s, &w| { s.push_str(w); s});
+    assert_eq!(pangram, "Pack my box with five dozen liquor jugs");
+}
+
+#[test]
+#[allow(clippy::iter_nth_zero)]
+fn nth_skips_items() {
+    let mut squares = (0..10).map(|i| i*i);
+
+    // nth()はインデックスnを引数とし、その数だけイテレータからのアイテムをスキップし、その次のアイテムを返す
+    // もうアイテムが無ければNoneを返す
+    // アダプタと違ってイテレータの所有権を受け取らないので、何度でも呼び出し可能
+    assert_eq!(squares.nth(4), Some(16));
+    assert_eq!(squares.nth(0), Some(25));
+    assert_eq!(squares.nth(6), None);
+}
+
+#[test]
+#[allow(clippy::double_ended_iterator_last)]
+fn last_consumes_every_item() {
+    // last()メソッドは対象のアイテムを先頭から最後まで消費する
+    // 消費する必要が無くて最後の要素を取り出したいならiter().rev().next()と書いた方がよい
+    let squares = (0..10).map(|i| i*i);
+    assert_eq!(squares.last(), Some(81));
+}
+
+#[test]
+fn find_returns_the_first_match() {
+    let populations = populations();
+
+    // find()はイテレータからアイテムを引き出し、引数のクロージャが最初にtrueとなったアイテムを返す
+    assert_eq!(populations.iter().find(|&(_name, &pop)| pop > 1_000_999), None);
+    assert_eq!(populations.iter().find(|&(_name, &pop)| pop > 500_999), Some((&"Portland", &583_776)));
+}
+
+#[test]
+fn extend_appends_to_a_collection() {
+    // 標準のコレクション（Stringを含む）は全てstd::iter::Extendを実装しているので拡張できる
+    // 配列やスライスは固定長なのでstd::iter::Extendを実装していない
+    let mut v: Vec<i32> = (0..5).map(|i| 1 << i).collect();
+    v.extend(&[31, 57, 99, 163]);
+    assert_eq!(v, &[1, 2, 4, 8, 16, 31, 57, 99, 163]);
+}
+
+#[test]
+fn partition_splits_into_two_collections() {
+    let things = ["doorknob", "mushroom", "noodle", "giraffe", "grapefruit"];
+
+    // partition()は引数のクロージャでコレクションを分割する
+    // 分割されるコレクションは同じ型でなければならない
+    let (living, nonliving): (Vec<&str>, Vec<&str>) = things.iter().partition(|name| name.as_bytes()[0] & 1 != 0);
+
+    assert_eq!(living,    vec!["mushroom", "giraffe", "grapefruit"]);
+    assert_eq!(nonliving, vec!["doorknob", "noodle"]);
+}
@@ -0,0 +1,75 @@
+#[test]
+fn contract_checker_reports_violated_laws() {
+    // contractモジュールで、自作イテレータがIteratorの約束を守っているかを検査できる
+    use rust_iterator_study::contract::{self, Law};
+    use rust_iterator_study::flakey::Flakey;
+
+    let violation = contract::check_iterator(|| Flakey(true)).unwrap_err();
+    assert_eq!(violation.law, Law::Fused);
+    assert!(contract::check_iterator(|| Flakey(true).fuse()).is_ok());
+
+    // 標準ライブラリのイテレータは全ての約束を守る
+    let bee_parts = ["head", "thorax", "abdomen"];
+    assert!(contract::check_exact_size(|| bee_parts.iter()).is_ok());
+    assert!(contract::check_double_ended(|| bee_parts.iter()).is_ok());
+    assert!(contract::check_double_ended(|| (0..20).filter(|n| n % 3 == 0)).is_ok());
+
+    // size_hintが実際より多い下限を返すと反例が報告される
+    struct Liar(u32);
+
+    impl Iterator for Liar {
+        type Item = u32;
+
+        fn next(&mut self) -> Option<u32> {
+            if self.0 == 0 {
+                None
+            } else {
+                self.0 -= 1;
+                Some(self.0)
+            }
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (self.0 as usize + 1, None)
+        }
+    }
+
+    let violation = contract::check_iterator(|| Liar(3)).unwrap_err();
+    assert_eq!(violation.law, Law::SizeHint);
+    assert_eq!(violation.counterexample,
+               "after 0 call(s) to next(), size_hint() returned (4, None) but 3 item(s) remained");
+
+    // next()とnext_back()が互いの位置を見ていないと、両端が出会った後も値を返してしまう
+    // 反例は長い列の終わりにしか現れないので、出会う位置の周りも検査される
+    struct Oblivious { front: u32, back: u32 }
+
+    impl Iterator for Oblivious {
+        type Item = u32;
+
+        fn next(&mut self) -> Option<u32> {
+            if self.front == 20 {
+                None
+            } else {
+                self.front += 1;
+                Some(self.front - 1)
+            }
+        }
+    }
+
+    impl DoubleEndedIterator for Oblivious {
+        fn next_back(&mut self) -> Option<u32> {
+            if self.back == 0 {
+                None
+            } else {
+                self.back -= 1;
+                Some(self.back)
+            }
+        }
+    }
+
+    let violation = contract::check_double_ended(|| Oblivious { front: 0, back: 20 }).unwrap_err();
+    assert_eq!(violation.law, Law::DoubleEnded);
+    assert_eq!(violation.counterexample,
+               "calling next() x 11, next_back(), next() x 9 returned Some(19) on the last call \
+                but expected None");
+}
@@ -0,0 +1,46 @@
+use rust_iterator_study::fizzbuzz::{fizz_buzz, FizzBuzz};
+
+#[test]
+fn standard_rules_match_fizz_buzz() {
+    assert!(FizzBuzz::standard().iter().take(1_000).eq(fizz_buzz().take(1_000)));
+}
+
+#[test]
+fn words_are_joined_in_rule_order() {
+    let rules = FizzBuzz::new().rule(5, "buzz").rule(3, "fizz");
+    assert_eq!(rules.iter().nth(14), Some("buzzfizz".to_string()));
+
+    let rules = FizzBuzz::standard().rule(7, "bazz");
+    let lines: Vec<String> = rules.iter().skip(19).take(3).collect();
+    assert_eq!(lines, ["buzz", "fizzbazz", "22"]);
+    assert_eq!(rules.iter().nth(104), Some("fizzbuzzbazz".to_string()));
+}
+
+#[test]
+fn predicate_rules_mix_with_periods() {
+    let rules = FizzBuzz::new()
+        .rule(3, "fizz")
+        .predicate("prime", |n| n > 1 && (2..n).take_while(|d| d * d <= n).all(|d| !n.is_multiple_of(d)));
+    let lines: Vec<String> = rules.iter().take(9).collect();
+    assert_eq!(lines, ["1", "prime", "fizzprime", "4", "prime", "fizz", "prime", "8", "fizz"]);
+}
+
+#[test]
+fn no_rules_prints_numbers_and_period_one_always_fires() {
+    let lines: Vec<String> = FizzBuzz::new().iter().take(3).collect();
+    assert_eq!(lines, ["1", "2", "3"]);
+    assert!(FizzBuzz::new().rule(1, "x").iter().take(50).all(|line| line == "x"));
+}
+
+#[test]
+fn write_to_any_writer() {
+    let mut out = Vec::new();
+    FizzBuzz::standard().write_to(&mut out, 5).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "1\n2\nfizz\n4\nbuzz\n");
+}
+
+#[test]
+#[should_panic(expected = "period must be positive")]
+fn zero_period_is_rejected() {
+    FizzBuzz::new().rule(0, "never");
+}
@@ -0,0 +1,68 @@
+use std::collections::BTreeSet;
+
+use rust_iterator_study::float_ord::{self, FloatOrdExt, NanError, NanPolicy, TotalF64};
+
+#[test]
+fn total_order_of_special_values() {
+    let negative_nan = -f64::NAN;
+    let mut values = vec![f64::NAN, 1.0, f64::INFINITY, -0.0, f64::NEG_INFINITY, 0.0, -1.5, negative_nan,
+                          f64::MIN_POSITIVE, f64::MAX];
+    float_ord::sort_floats(&mut values);
+
+    let bits: Vec<u64> = values.iter().map(|v| v.to_bits()).collect();
+    let expected: Vec<u64> = [negative_nan, f64::NEG_INFINITY, -1.5, -0.0, 0.0, f64::MIN_POSITIVE, 1.0, f64::MAX,
+                              f64::INFINITY, f64::NAN].iter().map(|v| v.to_bits()).collect();
+    assert_eq!(bits, expected);
+}
+
+#[test]
+fn total_f64_agrees_with_partial_cmp_on_ordinary_values() {
+    let values = [-1e300, -2.5, -1e-300, 0.0, 1e-300, 3.25, 7.0, 1e300];
+    for a in &values {
+        for b in &values {
+            assert_eq!(Some(TotalF64(*a).cmp(&TotalF64(*b))), a.partial_cmp(b), "{} vs {}", a, b);
+        }
+    }
+}
+
+#[test]
+fn total_f64_works_as_a_key() {
+    // NaN同士は等しく、-0.0と+0.0は別のキーになる
+    let set: BTreeSet<TotalF64> = [f64::NAN, 0.0, f64::NAN, -0.0, 0.0].iter().cloned().map(TotalF64).collect();
+    assert_eq!(set.len(), 3);
+    assert!(TotalF64(f64::NAN) == TotalF64(f64::NAN));
+    assert!(TotalF64(-0.0) < TotalF64(0.0));
+    assert_eq!(f64::from(TotalF64::from(2.5)), 2.5);
+    assert_eq!(TotalF64(2.5).to_string(), "2.5");
+}
+
+#[test]
+fn max_and_min_by_float_with_each_policy() {
+    let numbers = vec![3.0, f64::NAN, -7.0, 5.0, f64::NAN];
+
+    assert_eq!(numbers.iter().max_by_float(NanPolicy::Skip), Ok(Some(5.0)));
+    assert_eq!(numbers.iter().min_by_float(NanPolicy::Skip), Ok(Some(-7.0)));
+
+    assert!(numbers.iter().max_by_float(NanPolicy::Propagate).unwrap().unwrap().is_nan());
+    assert!(numbers.iter().min_by_float(NanPolicy::Propagate).unwrap().unwrap().is_nan());
+
+    let error = numbers.into_iter().max_by_float(NanPolicy::Error).unwrap_err();
+    assert_eq!(error, NanError { index: 1 });
+    assert_eq!(error.to_string(), "NaN at index 1");
+}
+
+#[test]
+fn max_and_min_by_float_without_nan() {
+    let numbers = [1.0, 4.0, 2.0];
+    for &policy in &[NanPolicy::Skip, NanPolicy::Propagate, NanPolicy::Error] {
+        assert_eq!(numbers.iter().max_by_float(policy), Ok(Some(4.0)));
+        assert_eq!(numbers.iter().min_by_float(policy), Ok(Some(1.0)));
+    }
+
+    // 空のイテレータやNaNしかない場合はNone
+    assert_eq!(std::iter::empty::<f64>().max_by_float(NanPolicy::Error), Ok(None));
+    assert_eq!([f64::NAN].iter().min_by_float(NanPolicy::Skip), Ok(None));
+
+    // 符号付きゼロも区別する
+    assert_eq!([0.0, -0.0].iter().min_by_float(NanPolicy::Skip).unwrap().unwrap().to_bits(), (-0.0f64).to_bits());
+}
@@ -0,0 +1,205 @@
+#[test]
+fn message_headers_unfold_and_body_stays_lazy() {
+    use rust_iterator_study::message::parse_message;
+
+    // take_while()では扱えない、折り返されたヘッダ（空白で始まる継続行）と単独の'\r'の行末を含むメッセージを読む
+    let message = "To: jimb\r\n\
+                   From: superego\r\n \
+                   <editor@oreilly.com>\r\
+                   subject: Fractals\n\
+                   \r\n\
+                   Did you get any writing done today?\r\n\
+                   When will you stop wasting time plotting fractals?\r\n";
+
+    let mut parsed = parse_message(message).unwrap();
+    let names: Vec<&str> = parsed.headers().iter().map(|h| h.name).collect();
+    assert_eq!(names, ["To", "From", "subject"]);
+
+    // 継続行は連結され、ヘッダ名は大文字小文字を区別せずに引ける
+    assert_eq!(parsed.get("from"), Some("superego <editor@oreilly.com>"));
+    assert_eq!(parsed.get("SUBJECT"), Some("Fractals"));
+    assert_eq!(parsed.get("Cc"), None);
+
+    // 本文はヘッダを読んだ残りの行のイテレータ
+    let body: Vec<&str> = parsed.body().collect();
+    assert_eq!(body, ["Did you get any writing done today?",
+                      "When will you stop wasting time plotting fractals?"]);
+}
+
+#[test]
+fn mime_multipart_parts_decode_lazily() {
+    use rust_iterator_study::mime::Part;
+
+    let message = "To: jimb\r\n\
+                   From: superego <editor@oreilly.com>\r\n\
+                   Content-Type: multipart/mixed; boundary=\"outer\"\r\n\
+                   \r\n\
+                   This is the preamble.\r\n\
+                   --outer\r\n\
+                   Content-Type: multipart/alternative; boundary=inner\r\n\
+                   \r\n\
+                   --inner\r\n\
+                   Content-Type: text/plain\r\n\
+                   Content-Transfer-Encoding: quoted-printable\r\n\
+                   \r\n\
+                   Did you get any writing d=\r\n\
+                   one today? gro=C3=9Fe\r\n\
+                   --inner\r\n\
+                   Content-Type: text/html\r\n\
+                   \r\n\
+                   <p>Did you?</p>\r\n\
+                   --inner--\r\n\
+                   --outer\r\n\
+                   Content-Type: application/octet-stream\r\n\
+                   Content-Transfer-Encoding: base64\r\n\
+                   \r\n\
+                   ZnJhY3Rh\r\n\
+                   bHM=\r\n\
+                   --outer--\r\n";
+
+    let root = Part::parse(message).unwrap();
+    assert_eq!(root.header("to"), Some("jimb"));
+
+    // 各パートは自分のヘッダと本文を持ち、入れ子のmultipartはさらにパートに分けられる
+    let parts: Vec<Part> = root.multipart().unwrap().collect::<Result<_, _>>().unwrap();
+    assert_eq!(parts.len(), 2);
+
+    let alternatives: Vec<Part> = parts[0].multipart().unwrap().collect::<Result<_, _>>().unwrap();
+    let types: Vec<&str> = alternatives.iter().map(|p| p.content_type()).collect();
+    assert_eq!(types, ["text/plain", "text/html"]);
+    assert_eq!(alternatives[1].body(), "<p>Did you?</p>");
+
+    // 転送エンコーディングはdecoded_body()のイテレータで必要な分だけデコードされる
+    let text: Vec<u8> = alternatives[0].decoded_body().collect();
+    assert_eq!(String::from_utf8(text).unwrap(), "Did you get any writing done today? große");
+    let attachment: Vec<u8> = parts[1].decoded_body().collect();
+    assert_eq!(attachment, b"fractals");
+}
+
+#[test]
+fn quoted_printable_drops_whitespace_at_the_end_of_a_line() {
+    use rust_iterator_study::mime::Part;
+
+    // 改行やソフト改行の直前の空白とタブは取り除き、行の途中の空白は残す
+    let part = Part::parse("Content-Transfer-Encoding: quoted-printable\r\n\
+                            \r\n\
+                            abc \t=\r\n\
+                            def =  \r\n\
+                            g h\t\r\n\
+                            end  ").unwrap();
+    let text: Vec<u8> = part.decoded_body().collect();
+    assert_eq!(String::from_utf8(text).unwrap(), "abcdefg h\r\nend");
+}
+
+#[test]
+fn mbox_yields_one_message_at_a_time() {
+    use rust_iterator_study::mbox::Mbox;
+
+    let mailbox = "From superego Mon Jan  1 00:00:00 2018\n\
+                   To: jimb\n\
+                   Subject: writing\n\
+                   \n\
+                   Did you get any writing done today?\n\
+                   >From the desk of your superego.\n\
+                   \n\
+                   From jimb Mon Jan  1 09:00:00 2018\n\
+                   To: superego\n\
+                   Subject: Re: writing\n\
+                   \n\
+                   Fractals.\n";
+
+    // BufReadから1通ずつメッセージを取り出す
+    let messages: Vec<_> = Mbox::new(mailbox.as_bytes())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[1].envelope(), "jimb Mon Jan  1 09:00:00 2018");
+
+    // 本文中の">From "は"From "に戻される
+    let mut first = messages[0].message().unwrap();
+    assert_eq!(first.get("subject"), Some("writing"));
+    let body: Vec<&str> = first.body().collect();
+    assert_eq!(body, ["Did you get any writing done today?", "From the desk of your superego."]);
+
+    // part()でヘッダと本文のスライスに分け、件名の一覧を作る
+    let subjects: Vec<String> = Mbox::new(mailbox.as_bytes())
+        .map(|m| m.unwrap().part().unwrap().header("Subject").unwrap().to_string())
+        .collect();
+    assert_eq!(subjects, ["writing", "Re: writing"]);
+}
+
+#[test]
+fn json_reader_yields_events() {
+    use rust_iterator_study::json::{self, Event};
+
+    let text = r#"{"city": "Portland", "population": 583776,
+                   "neighbors": [{"city": "Fossil"}, {"city": "Greenhorn"}],
+                   "big": true}"#;
+
+    // DOMを作らず、イベントのイテレータとして読む
+    let events: Vec<Event> = json::from_str(text)
+        .take(5)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(events, vec![
+        Event::StartObject,
+        Event::Key("city".to_string()),
+        Event::String("Portland".to_string()),
+        Event::Key("population".to_string()),
+        Event::Number(583776.0)
+    ]);
+
+    // 興味のない部分木はskip_value()で読み飛ばせる
+    let mut reader = json::from_str(text);
+    let mut keys = Vec::new();
+    while let Some(event) = reader.next() {
+        if let Event::Key(key) = event.unwrap() {
+            if reader.depth() == 1 && key == "neighbors" {
+                reader.skip_value().unwrap();
+            }
+            keys.push(key);
+        }
+    }
+    assert_eq!(keys, ["city", "population", "neighbors", "big"]);
+
+    // io::Readからも同じように読め、エラーは行と列の位置を持つ
+    let broken = "[1, 2,\n 3,]";
+    let err = json::from_reader(broken.as_bytes())
+        .find_map(Result::err)
+        .unwrap();
+    assert_eq!((err.position.line, err.position.column), (2, 4));
+    assert_eq!(err.to_string(), "unexpected character ']' at line 2, column 4");
+}
+
+#[test]
+fn csv_reader_handles_quotes_and_types() {
+    use rust_iterator_study::csv::Reader;
+
+    // split_whitespace()やsplit(',')では扱えない、引用符の中の区切り文字や改行も正しく読める
+    let text = "city,population,motto\n\
+                Portland,583776,\"Keep Portland \"\"Weird\"\"\"\n\
+                \"The Dalles\",15340,\"Gateway,\nto the Gorge\"\n\
+                Greenhorn,two,\n";
+    let mut reader = Reader::new(text.as_bytes()).has_headers(true);
+    assert_eq!(reader.headers().unwrap().unwrap(), ["city", "population", "motto"]);
+
+    let records: Vec<_> = reader.collect::<Result<_, _>>().unwrap();
+    assert_eq!(records[0].get(2), Some("Keep Portland \"Weird\""));
+    assert_eq!(records[1].get(0), Some("The Dalles"));
+    assert_eq!(records[1].get(2), Some("Gateway,\nto the Gorge"));
+
+    // 型変換に失敗すると、そのレコードの行番号とフィールドの番号を報告する
+    assert_eq!(records[1].parse_by_name::<u32>("population").unwrap(), 15340);
+    let err = records[2].parse_by_name::<u32>("population").unwrap_err();
+    assert_eq!((err.row, err.column), (5, 2));
+    assert_eq!(err.to_string(), "row 5, column 2: cannot parse \"two\": invalid digit found in string");
+
+    // 区切り文字を変えればTSVも読める
+    let tsv = "1\t2.5\n3\t4.5\n";
+    let sum: f64 = Reader::new(tsv.as_bytes())
+        .delimiter('\t')
+        .map(|r| r.and_then(|r| r.parse::<f64>(1)))
+        .sum::<Result<f64, _>>()
+        .unwrap();
+    assert_eq!(sum, 7.0);
+}
@@ -0,0 +1,184 @@
+// 書籍の説明どおりの書き方（あえて冗長なものを含む）を残しているテストでは、それに対するlintだけを許可する
+
+use std::collections::BTreeSet;
+use std::ffi::OsStr;
+use std::iter::FromIterator;
+use std::path::Path;
+
+use rust_iterator_study::flakey::Flakey;
+use rust_iterator_study::range::I32Range;
+use rust_iterator_study::tree::{make_node, BinaryTree};
+
+#[test]
+#[allow(clippy::into_iter_on_ref, clippy::while_let_on_iterator)]
+fn for_loop_is_sugar_for_into_iter_and_next() {
+    let v = vec!["antimony", "arsenic", "alumium", "selenium"];
+    let mut looped = Vec::new();
+    for element in &v {
+        looped.push(*element);
+    }
+
+    // 上のループは以下と同等
+    let mut desugared = Vec::new();
+    let mut iterator = (&v).into_iter();
+    while let Some(element) = iterator.next() {
+        // Some(element)が返されたらループボディ部を実行するがNoneが返されたら終了する
+        desugared.push(*element);
+    }
+    assert_eq!(looped, desugared);
+}
+
+#[test]
+#[allow(clippy::useless_vec)]
+fn vec_iter_returns_none_after_the_last_item() {
+    let v = vec![4, 20, 12, 8, 6];
+    let mut iterator = v.iter();
+    assert_eq!(iterator.next(), Some(&4));
+    assert_eq!(iterator.next(), Some(&20));
+    assert_eq!(iterator.next(), Some(&12));
+    assert_eq!(iterator.next(), Some(&8));
+    assert_eq!(iterator.next(), Some(&6));
+    assert_eq!(iterator.next(), None);
+    // 最後の要素Noneを返したあとでさらにnext()を呼んだ時に何を返すかをIteratorトレイトは規定していないが、多くの実装では再度Noneを返す
+    assert_eq!(iterator.next(), None);
+}
+
+#[test]
+fn path_iter_yields_components() {
+    let path = Path::new("C:/Users/Jimb/Downloads/Fedra.iso");
+    let mut iterator = path.iter();
+    assert_eq!(iterator.next(), Some(OsStr::new("C:")));
+    assert_eq!(iterator.next(), Some(OsStr::new("Users")));
+    assert_eq!(iterator.next(), Some(OsStr::new("Jimb")));
+    assert_eq!(iterator.next(), Some(OsStr::new("Downloads")));
+    assert_eq!(iterator.next(), Some(OsStr::new("Fedra.iso")));
+    assert_eq!(iterator.next(), None);
+    assert_eq!(iterator.next(), None);
+}
+
+#[test]
+fn btree_set_into_iter_yields_owned_items_in_order() {
+    // HashSetでなくイテレータの順序が保証されるBTreeSetを使う
+    let mut favorites = BTreeSet::new();
+    favorites.insert("Lucy in the Sky With Diamonds".to_string());
+    favorites.insert("Libebestramue No. 3".to_string());
+
+    // favorites.into_iter()は所有権ごと返すイテレータなので、この後favoritesは使えない
+    let mut it = favorites.into_iter();
+    assert_eq!(it.next(), Some("Libebestramue No. 3".to_string()));
+    assert_eq!(it.next(), Some("Lucy in the Sky With Diamonds".to_string()));
+    assert_eq!(it.next(), None);
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn drain_borrows_a_range_mutably() {
+    // 多くのコレクションはdrain()を実装しており、指定範囲の可変参照を借用したイテレータを返す
+    let mut outer = "Earth".to_string();
+    let inner = String::from_iter(outer.drain(1..4));
+
+    assert_eq!(outer, "Eh");
+    assert_eq!(inner, "art");
+}
+
+#[test]
+fn flakey_yields_again_after_none() {
+    // next()を呼んでNoneになっても再び要素が返される
+    let mut flaky = Flakey(true);
+    assert_eq!(flaky.next(), Some("totaly the last item"));
+    assert_eq!(flaky.next(), None);
+    assert_eq!(flaky.next(), Some("totaly the last item"));
+}
+
+#[test]
+fn fuse_keeps_returning_none() {
+    // fuseアダプタにより、最後の要素に到達したら常にNoneを返すイテレータに変換可能
+    let mut not_flaky = Flakey(true).fuse();
+    assert_eq!(not_flaky.next(), Some("totaly the last item"));
+    assert_eq!(not_flaky.next(), None);
+    assert_eq!(not_flaky.next(), None);
+    assert_eq!(not_flaky.next(), None);
+}
+
+#[test]
+fn double_ended_iterator_meets_in_the_middle() {
+    let bee_parts = ["head", "thorax", "abdomen"];
+
+    // DoubleEndedIteratorを実装している型は前端と後端のアイテムを引き出せる
+    let mut iter = bee_parts.iter();
+    assert_eq!(iter.next(),      Some(&"head"));
+    assert_eq!(iter.next_back(), Some(&"abdomen"));
+    assert_eq!(iter.next(),      Some(&"thorax"));
+    // 2本の指が一致したところで繰り返し実行は終了する
+    assert_eq!(iter.next_back(), None);
+    assert_eq!(iter.next(),      None);
+}
+
+#[test]
+fn rev_swaps_next_and_next_back() {
+    let meals = ["breakfast", "lunch", "dinner"];
+
+    // rev()でnext/next_backが反転したイテレータを取得できる
+    let mut rev_iter = meals.iter().rev();
+    assert_eq!(rev_iter.next(), Some(&"dinner"));
+    assert_eq!(rev_iter.next(), Some(&"lunch"));
+    assert_eq!(rev_iter.next(), Some(&"breakfast"));
+    assert_eq!(rev_iter.next(), None);
+}
+
+#[test]
+fn i32_range_computes_pi() {
+    let mut pi = 0.0;
+    let mut numerator = 1.0;
+
+    // I32RangeはIteratorを実装しているためfor文で利用できる
+    for k in (I32Range { start: 0, end: 14 }) {
+        pi += numerator / (2*k + 1) as f64;
+        numerator /= -3.0;
+    }
+
+    pi *= f64::sqrt(12.0);
+
+    // IEEE754に準拠するとconsts::PIと完全に一致する
+    assert_eq!(pi as f32, std::f32::consts::PI);
+}
+
+fn jaeger_tree() -> BinaryTree<&'static str> {
+    let subtree_l = make_node(BinaryTree::Empty, "mecha", BinaryTree::Empty);
+    let subtree_rl = make_node(BinaryTree::Empty, "droid", BinaryTree::Empty);
+    let subtree_r = make_node(subtree_rl, "robot", BinaryTree::Empty);
+    make_node(subtree_l, "Jaeger", subtree_r)
+}
+
+#[test]
+fn binary_tree_iterates_in_order() {
+    let tree = jaeger_tree();
+
+    let mut v = Vec::new();
+    for kind in &tree {
+        // 左にあるノードから順にsubtree_l, tree, subtree_rl, subtree_rの順にイテレートされる
+        v.push(*kind);
+    }
+
+    assert_eq!(v, ["mecha", "Jaeger", "droid", "robot"]);
+}
+
+#[test]
+fn binary_tree_works_with_adapters() {
+    let tree = jaeger_tree();
+
+    // 型BinaryTreeはアダプタが利用できる
+    assert_eq!(tree.iter()
+                .map(|name| format!("mega-{}", name))
+                .collect::<Vec<_>>(),
+                vec!["mega-mecha", "mega-Jaeger", "mega-droid", "mega-robot"]);
+}
+
+#[test]
+fn binary_tree_add_keeps_elements_sorted() {
+    let mut tree = BinaryTree::Empty;
+    for word in ["robot", "mecha", "droid", "Jaeger"].iter() {
+        tree.add(*word);
+    }
+    assert_eq!(tree.iter().cloned().collect::<Vec<_>>(), ["Jaeger", "droid", "mecha", "robot"]);
+}
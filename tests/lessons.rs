@@ -0,0 +1,23 @@
+use std::collections::HashSet;
+
+use rust_iterator_study::lessons;
+
+#[test]
+fn every_lesson_passes() {
+    for lesson in lessons::all() {
+        let mut out = Vec::new();
+        (lesson.run)(&mut out).unwrap();
+        assert!(!out.is_empty(), "{} printed nothing", lesson.name);
+    }
+}
+
+#[test]
+fn lesson_names_are_unique_and_findable() {
+    let mut names = HashSet::new();
+    for lesson in lessons::all() {
+        assert!(names.insert(lesson.name), "duplicate lesson {}", lesson.name);
+        assert_eq!(lessons::find(lesson.name).map(|l| l.topic), Some(lesson.topic));
+    }
+    assert!(lessons::find("no-such-lesson").is_none());
+    assert_eq!(lessons::topics(), ["iteration", "adapters", "consumers", "parsers", "formats", "testing"]);
+}
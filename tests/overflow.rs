@@ -0,0 +1,50 @@
+use rust_iterator_study::overflow::OverflowExt;
+
+#[test]
+fn checked_sum_and_product() {
+    assert_eq!((1..=20u64).checked_product(), Some(2432902008176640000));
+    assert_eq!((1..=21u64).checked_product(), None);
+    assert_eq!([100u8, 100, 55].iter().checked_sum(), Some(255));
+    assert_eq!([100u8, 100, 56].iter().checked_sum(), None);
+    assert_eq!([i8::MIN, -1].iter().checked_sum(), None);
+    assert_eq!([-128i8, 127, -1].iter().checked_sum(), Some(-2));
+
+    // 空のイテレータでは単位元になる
+    assert_eq!(std::iter::empty::<i32>().checked_sum(), Some(0));
+    assert_eq!(std::iter::empty::<i32>().checked_product(), Some(1));
+}
+
+#[test]
+fn checked_sum_stops_at_the_first_overflow() {
+    let mut consumed = 0;
+    let result = (0..).map(|i| { consumed += 1; if i == 0 { u32::MAX } else { 1 } }).take(1_000).checked_sum();
+    assert_eq!(result, None);
+    assert_eq!(consumed, 2);
+}
+
+#[test]
+fn saturating_sum_and_product() {
+    assert_eq!([200u8, 100].iter().saturating_sum(), u8::MAX);
+    assert_eq!([i16::MIN, -1, 5].iter().saturating_sum(), i16::MIN + 5);
+    assert_eq!([-2i64, i64::MAX].iter().saturating_product(), i64::MIN);
+    assert_eq!((1..=40u128).saturating_product(), u128::MAX);
+}
+
+#[test]
+fn wrapping_sum_and_product() {
+    assert_eq!([200u8, 100].iter().wrapping_sum(), 44);
+    assert_eq!([i32::MAX, 1].iter().wrapping_sum(), i32::MIN);
+    assert_eq!((1..=21u64).wrapping_product(), 14197454024290336768);
+    assert_eq!([3usize, 5].iter().wrapping_product(), 15);
+}
+
+#[test]
+fn widening_sum_does_not_overflow() {
+    assert_eq!(vec![u64::MAX; 1_000].into_iter().widening_sum(), Some(1_000 * u64::MAX as u128));
+    assert_eq!([i64::MIN, i64::MIN, 1].iter().widening_sum(), Some(2 * i64::MIN as i128 + 1));
+    assert_eq!([255u8; 10].iter().widening_sum(), Some(2_550u128));
+    assert_eq!([-1isize, 2].iter().widening_sum(), Some(1i128));
+
+    // 128ビットの型同士ではそれ以上広げられないので、オーバーフローするとNone
+    assert_eq!([u128::MAX, 1].iter().widening_sum(), None);
+}
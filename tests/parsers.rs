@@ -0,0 +1,162 @@
+#[test]
+fn parse_partition_keeps_rejected_tokens() {
+    use std::str::FromStr;
+    use rust_iterator_study::parse_all::ParseAllExt;
+
+    let text = "1\nfrond .25 289\n3.1415 estuary\n";
+
+    // ok()で捨てていたエラーを、何番目のどのトークンだったかと一緒に残す
+    // 記録するエラーは先頭から1件までに制限し、残りは件数だけを数える
+    let result = text.split_whitespace()
+        .parse_partition(1, |w| f64::from_str(w));
+    assert_eq!(result.parsed.len(), 4);
+    assert_eq!(result.parsed[..3], [1.0, 0.25, 289.0]);
+    assert_eq!(result.rejected_count, 2);
+    assert_eq!(result.rejected[0].index, 1);
+    assert_eq!(result.rejected[0].token, "frond");
+    assert_eq!(result.unrecorded(), 1);
+    assert_eq!(result.rejected[0].to_string(), "item 1 (\"frond\"): invalid float literal");
+
+    // 最初のエラーで止める場合
+    let err = text.split_whitespace()
+        .try_parse_all(|w| f64::from_str(w))
+        .unwrap_err();
+    assert_eq!((err.index, err.token), (1, "frond"));
+    assert_eq!("4 9 16".split(' ').try_parse_all(|w| w.parse::<u32>()), Ok(vec![4, 9, 16]));
+}
+
+#[test]
+fn parse_float_reads_from_peekable() {
+    use rust_iterator_study::float_parse::parse_float;
+
+    // parse_number()と同じくPeekableから数値を読み取り、区切り文字は消費せずに残す
+    let mut chars = "-12.5e3,.25,INF,nan".chars().peekable();
+    assert_eq!(parse_float(&mut chars), Ok(-12500.0));
+    assert_eq!(chars.next(), Some(','));
+    assert_eq!(parse_float(&mut chars), Ok(0.25));
+    assert_eq!(chars.next(), Some(','));
+    assert_eq!(parse_float(&mut chars), Ok(f64::INFINITY));
+    assert_eq!(chars.next(), Some(','));
+    assert!(parse_float(&mut chars).unwrap().is_nan());
+    assert_eq!(chars.next(), None);
+
+    // 丸めはf64::from_str()と同じ結果になる
+    let text = "2.2250738585072011e-308";
+    assert_eq!(parse_float(&mut text.chars().peekable()).unwrap(), text.parse::<f64>().unwrap());
+
+    // 不正な入力は何文字目で失敗したかを返す
+    let err = parse_float(&mut "1.5e+x".chars().peekable()).unwrap_err();
+    assert_eq!(err.position, 5);
+    assert_eq!(err.found, Some('x'));
+    assert_eq!(err.to_string(), "expected exponent digit at position 5, found 'x'");
+}
+
+#[test]
+fn lexer_yields_tokens_with_positions() {
+    use rust_iterator_study::lexer::{Lexer, TokenKind};
+
+    let source = "let ratio = 2.5; // circle\nname = \"Jaeger\\n\" ` 42";
+
+    // Lexerはトークン列を生成するイテレータなので、アダプタをそのまま繋げられる
+    let kinds: Vec<TokenKind> = Lexer::new(source.chars())
+        .map(|token| token.kind)
+        .collect();
+    assert_eq!(kinds, vec![
+        TokenKind::Ident("let".to_string()),
+        TokenKind::Ident("ratio".to_string()),
+        TokenKind::Punct('='),
+        TokenKind::Float(2.5),
+        TokenKind::Punct(';'),
+        TokenKind::Comment(" circle".to_string()),
+        TokenKind::Ident("name".to_string()),
+        TokenKind::Punct('='),
+        TokenKind::Str("Jaeger\n".to_string()),
+        // 不正な文字があってもエラートークンを返して解析を続ける
+        TokenKind::Error("unexpected character '`'".to_string()),
+        TokenKind::Int(42)
+    ]);
+
+    // 各トークンは行と列の位置を持つ
+    let columns: Vec<usize> = Lexer::new(source.chars())
+        .filter(|t| t.span.start.line == 2)
+        .map(|t| t.span.start.column)
+        .collect();
+    assert_eq!(columns, [1, 6, 8, 19, 21]);
+}
+
+#[test]
+fn multipeek_looks_ahead_any_distance() {
+    use rust_iterator_study::multipeek::MultiPeekExt;
+
+    // Peekableでは1文字先しか見えないため"..="と".."を区別できないが、MultiPeekなら何文字先でも覗ける
+    let mut chars = "1..=10".chars().multipeek();
+    assert_eq!(chars.next_if(|c| c.is_ascii_digit()), Some('1'));
+    assert_eq!(chars.peek_nth(0), Some(&'.'));
+    assert_eq!(chars.peek_nth(1), Some(&'.'));
+    assert_eq!(chars.peek_nth(2), Some(&'='));
+
+    // 覗いただけのアイテムは消費されない
+    assert_eq!(chars.next_if_eq(&'.'), Some('.'));
+    assert_eq!(chars.next_if_eq(&'='), None);
+    assert_eq!(chars.next(), Some('.'));
+
+    // 取り出したアイテムはput_back()で戻せる
+    let c = chars.next().unwrap();
+    chars.put_back(c);
+    assert_eq!(chars.next(), Some('='));
+
+    // peek_while()はpredicateを満たす間のアイテムを消費せずに返す
+    assert_eq!(chars.peek_while(|c| c.is_ascii_digit()).collect::<String>(), "10");
+    assert_eq!(chars.collect::<String>(), "10");
+
+    // Lexerも内部でMultiPeekを使い、"0x"や範囲の".."を数値と区別する
+    use rust_iterator_study::lexer::{Lexer, TokenKind};
+    let kinds: Vec<TokenKind> = Lexer::new("0..0x1F".chars()).map(|t| t.kind).collect();
+    assert_eq!(kinds, vec![TokenKind::Int(0), TokenKind::Punct('.'), TokenKind::Punct('.'), TokenKind::Int(31)]);
+    let kinds: Vec<TokenKind> = Lexer::new("0X1f 1else 1e".chars()).map(|t| t.kind).collect();
+    assert_eq!(kinds, vec![
+        TokenKind::Int(31),
+        TokenKind::Error("invalid number \"1else\"".to_string()),
+        TokenKind::Error("missing exponent digits in \"1e\"".to_string())
+    ]);
+}
+
+#[test]
+fn combinators_rebuild_parse_number() {
+    use rust_iterator_study::combinator::{Input, Parser, eof, left, many1, map, satisfy, sep_by, token};
+
+    // parse_number()のループを、小さなパーサを組み合わせて書き直したもの
+    let digit = satisfy("digit", |c: &char| c.is_ascii_digit());
+    let number = map(many1(digit), |digits: Vec<char>| {
+        digits.iter().fold(0, |n, d| n * 10 + d.to_digit(10).unwrap())
+    });
+    let numbers = left(sep_by(number, token(',')), eof());
+
+    let mut input = Input::new("226153980,1766319049".chars());
+    assert_eq!(numbers.parse(&mut input), Ok(vec![226153980, 1766319049]));
+
+    // 失敗した時は最も先まで進んだ位置と、そこで期待されていたものを報告する
+    let err = numbers.parse(&mut Input::new("226153980,17x".chars())).unwrap_err();
+    assert_eq!(err.position, 12);
+    assert_eq!(err.to_string(), "at position 12: expected one of ',', digit, end of input, found 'x'");
+
+    // 文字だけでなくLexerが生成するトークン列にも同じコンビネータが使える
+    use rust_iterator_study::lexer::{Lexer, Token, TokenKind};
+    let int = map(satisfy("integer", |t: &Token| matches!(t.kind, TokenKind::Int(_))), |t: Token| {
+        match t.kind { TokenKind::Int(n) => n, _ => unreachable!() }
+    });
+    let comma = satisfy("','", |t: &Token| t.kind == TokenKind::Punct(','));
+    let ints = left(sep_by(int, comma), eof());
+    assert_eq!(ints.parse(&mut Input::new(Lexer::new("1, 2, 0x10".chars()))), Ok(vec![1, 2, 16]));
+}
+
+#[test]
+fn sep_by_stops_when_nothing_is_consumed() {
+    use rust_iterator_study::combinator::{Input, Parser, optional, sep_by, token};
+
+    // 区切りも要素も入力を消費せずに成功するので、1回繰り返したところで打ち切られる
+    let items = sep_by(optional(token('x')), optional(token(',')));
+    let mut input = Input::new("y".chars());
+    assert_eq!(items.parse(&mut input), Ok(vec![None, None]));
+    assert_eq!(input.position(), 0);
+}
@@ -0,0 +1,171 @@
+use rust_iterator_study::contract;
+use rust_iterator_study::fizzbuzz::fizz_buzz;
+use rust_iterator_study::multipeek::MultiPeekExt;
+use rust_iterator_study::number::parse_number;
+use rust_iterator_study::property::{self, quickcheck, Arbitrary, Config, Failure, Rng};
+use rust_iterator_study::range::I32Range;
+use rust_iterator_study::tree::BinaryTree;
+
+// 再帰で素直に書いた通りがけ順。TreeIterの結果と比べるための基準
+fn in_order<T: Clone>(tree: &BinaryTree<T>, out: &mut Vec<T>) {
+    if let BinaryTree::NonEmpty(ref node) = *tree {
+        in_order(&node.left, out);
+        out.push(node.element.clone());
+        in_order(&node.right, out);
+    }
+}
+
+#[test]
+fn multipeek_peek_nth_matches_indexing() {
+    quickcheck(|&(ref v, n): &(Vec<i32>, u8)| {
+        let n = n as usize;
+        let mut iter = v.iter().cloned().multipeek();
+        iter.peek_nth(n).cloned() == v.get(n).cloned() && iter.collect::<Vec<_>>() == *v
+    });
+}
+
+#[test]
+fn multipeek_obeys_iterator_contract() {
+    quickcheck(|v: &Vec<i32>| contract::check_iterator(|| v.iter().multipeek()).is_ok());
+}
+
+#[test]
+fn i32_range_matches_std_range() {
+    quickcheck(|&(start, len): &(i32, u8)| {
+        let end = start.saturating_add(len as i32);
+        (I32Range { start, end }).eq(start..end)
+    });
+}
+
+#[test]
+fn i32_range_is_empty_when_start_is_not_below_end() {
+    quickcheck(|&(start, end): &(i32, i32)| {
+        start < end || (I32Range { start, end }).next().is_none()
+    });
+}
+
+#[test]
+fn i32_range_obeys_iterator_contract() {
+    quickcheck(|&(start, len): &(i32, u8)| {
+        let end = start.saturating_add(len as i32);
+        contract::check_iterator(|| I32Range { start, end }).is_ok()
+    });
+}
+
+#[test]
+fn tree_iter_visits_nodes_in_order() {
+    quickcheck(|tree: &BinaryTree<i32>| {
+        let mut expected = Vec::new();
+        in_order(tree, &mut expected);
+        tree.iter().cloned().eq(expected)
+    });
+}
+
+#[test]
+fn tree_built_with_add_iterates_sorted() {
+    quickcheck(|values: &Vec<i32>| {
+        let mut tree = BinaryTree::Empty;
+        for &v in values {
+            tree.add(v);
+        }
+        let mut sorted = values.clone();
+        sorted.sort();
+        tree.iter().cloned().eq(sorted)
+    });
+}
+
+#[test]
+fn tree_iter_obeys_iterator_contract() {
+    quickcheck(|tree: &BinaryTree<String>| {
+        contract::check_iterator(|| tree.iter()).is_ok()
+    });
+}
+
+#[test]
+fn parse_number_reads_digits_and_stops_before_the_rest() {
+    quickcheck(|&(n, ref rest): &(u32, String)| {
+        if rest.starts_with(|c: char| c.is_ascii_digit()) {
+            return true;
+        }
+        let text = format!("{}{}", n, rest);
+        let mut chars = text.chars().peekable();
+        parse_number(&mut chars) == n && chars.eq(rest.chars())
+    });
+}
+
+#[test]
+fn parse_number_ignores_leading_zeros() {
+    quickcheck(|&(n, zeros): &(u32, u8)| {
+        let text = format!("{}{}", "0".repeat(zeros as usize), n);
+        parse_number(&mut text.chars().peekable()) == n
+    });
+}
+
+#[test]
+fn fizz_buzz_follows_the_rules() {
+    quickcheck(|&i: &u16| {
+        let n = i as u32 + 1;
+        let expected = match (n % 3, n % 5) {
+            (0, 0) => "fizzbuzz".to_string(),
+            (0, _) => "fizz".to_string(),
+            (_, 0) => "buzz".to_string(),
+            _ => n.to_string()
+        };
+        fizz_buzz().nth(i as usize) == Some(expected)
+    });
+}
+
+#[test]
+fn unsigned_integers_are_drawn_uniformly_up_to_size() {
+    let mut rng = Rng::new(1);
+    let mut counts = [0; 11];
+    for _ in 0..1000 {
+        let x = u32::arbitrary(&mut rng, 10);
+        if x <= 10 {
+            counts[x as usize] += 1;
+        }
+    }
+    // 8回に1回は全範囲から選ぶので、0..=10の各値はおよそ80回ずつ出る
+    assert!(counts.iter().all(|&n| 40 < n && n < 130), "{:?}", counts);
+}
+
+#[test]
+fn failing_integers_shrink_to_the_boundary() {
+    let config = Config::new().seed(1);
+    let failure = property::check(&config, |&x: &i32| x < 100).unwrap_err();
+    assert_eq!(failure.shrunk, 100);
+}
+
+#[test]
+fn failing_vectors_shrink_to_a_minimal_counterexample() {
+    let config = Config::new().seed(1);
+    let failure = property::check(&config, |v: &Vec<i32>| v.len() < 3).unwrap_err();
+    assert_eq!(failure.shrunk, vec![0, 0, 0]);
+
+    let failure = property::check(&config, |v: &Vec<u8>| !v.contains(&7)).unwrap_err();
+    assert_eq!(failure.shrunk, vec![7]);
+}
+
+#[test]
+fn failing_trees_shrink_to_a_single_node() {
+    let config = Config::new().seed(1);
+    let failure = property::check(&config, |tree: &BinaryTree<i32>| tree.iter().all(|&x| x < 10))
+        .unwrap_err();
+    assert_eq!(failure.shrunk.iter().collect::<Vec<_>>(), [&10]);
+}
+
+#[test]
+fn same_seed_reproduces_the_same_failure() {
+    let prop = |s: &String| !s.contains('z');
+    let first: Failure<String> = property::check(&Config::new().seed(42), prop).unwrap_err();
+    let second = property::check(&Config::new().seed(42), prop).unwrap_err();
+    assert_eq!(first, second);
+    assert_eq!(first.shrunk, "z");
+    assert!(first.to_string().contains("PROPERTY_SEED=42"));
+}
+
+#[test]
+fn passing_property_checks_every_case() {
+    let config = Config::new().cases(500).max_size(10);
+    assert_eq!(property::check(&config, |v: &Vec<u8>| v.len() <= 10), Ok(()));
+}
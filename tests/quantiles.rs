@@ -0,0 +1,151 @@
+use rust_iterator_study::histogram::{Bucket, Buckets, Histogram};
+use rust_iterator_study::quantile::QuantileSketch;
+
+// 重複や偏りのある、並べ替えられていない値の列
+fn stream(n: u64) -> Vec<f64> {
+    (0..n).map(|i| {
+        let x = (i * 2_654_435_761) % 1_000_003;
+        (x % 5_000) as f64 * if x % 3 == 0 { 10.0 } else { 1.0 }
+    }).collect()
+}
+
+fn sorted(values: &[f64]) -> Vec<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    sorted
+}
+
+// valueが占める順位の範囲が、正確な順位から±εnの範囲と重なること
+fn assert_rank_within(sorted: &[f64], phi: f64, value: f64, epsilon: f64) {
+    let n = sorted.len() as f64;
+    let rank = (phi * n).ceil().max(1.0);
+    let lowest = sorted.iter().take_while(|&&x| x < value).count() as f64 + 1.0;
+    let highest = sorted.iter().take_while(|&&x| x <= value).count() as f64;
+    assert!(highest >= lowest, "{} is not in the input", value);
+    assert!(lowest <= rank + epsilon * n && highest >= rank - epsilon * n,
+            "phi = {}: {} has ranks {}..={}, expected {} ± {}", phi, value, lowest, highest, rank, epsilon * n);
+}
+
+#[test]
+fn sketch_quantiles_are_within_epsilon() {
+    let values = stream(50_000);
+    let exact = sorted(&values);
+
+    for &epsilon in &[0.1, 0.01, 0.001] {
+        let mut sketch = QuantileSketch::new(epsilon);
+        sketch.extend(&values);
+        assert_eq!(sketch.count(), 50_000);
+        for i in 0..=100 {
+            let phi = i as f64 / 100.0;
+            assert_rank_within(&exact, phi, sketch.quantile(phi).unwrap(), epsilon);
+        }
+        assert_rank_within(&exact, 0.999, sketch.quantile(0.999).unwrap(), epsilon);
+    }
+}
+
+#[test]
+fn sketch_keeps_far_fewer_values_than_the_input() {
+    let sketch: QuantileSketch = stream(100_000).into_iter().collect();
+    assert!(sketch.summary_len() < 5_000, "{}", sketch.summary_len());
+
+    let coarse: QuantileSketch = {
+        let mut sketch = QuantileSketch::new(0.01);
+        sketch.extend(stream(100_000));
+        sketch
+    };
+    assert!(coarse.summary_len() < 500, "{}", coarse.summary_len());
+}
+
+#[test]
+fn sketch_is_exact_at_the_extremes_and_for_small_input() {
+    let values = stream(10_000);
+    let exact = sorted(&values);
+    let sketch = {
+        let mut sketch = QuantileSketch::new(0.05);
+        sketch.extend(&values);
+        sketch
+    };
+    assert_eq!(sketch.quantile(0.0), exact.first().cloned());
+    assert_eq!(sketch.quantile(1.0), exact.last().cloned());
+
+    let small: QuantileSketch = vec![3.0, 1.0, 2.0].into_iter().collect();
+    assert_eq!(small.median(), Some(2.0));
+    assert_eq!(QuantileSketch::default().median(), None);
+}
+
+#[test]
+fn fixed_histogram_counts_each_interval() {
+    let mut histogram = Histogram::fixed(10.0);
+    histogram.extend(vec![-3.0, 0.0, 9.99, 10.0, 25.0, 29.0, f64::NAN]);
+    let buckets: Vec<Bucket> = histogram.buckets().collect();
+    assert_eq!(buckets, vec![Bucket { low: -10.0, high: 0.0, count: 1 },
+                             Bucket { low: 0.0, high: 10.0, count: 2 },
+                             Bucket { low: 10.0, high: 20.0, count: 1 },
+                             Bucket { low: 20.0, high: 30.0, count: 2 }]);
+    assert_eq!(histogram.count(), 6);
+    assert_eq!(histogram.nan_count(), 1);
+}
+
+#[test]
+fn values_beyond_the_bucket_index_range_are_counted_outside_the_buckets() {
+    let mut histogram = Histogram::fixed(1.0);
+    histogram.extend(vec![1e300, -1e300, 3.5]);
+    assert_eq!(histogram.overflow(), 1);
+    assert_eq!(histogram.underflow(), 1);
+    assert_eq!(histogram.buckets().collect::<Vec<_>>(), vec![Bucket { low: 3.0, high: 4.0, count: 1 }]);
+}
+
+#[test]
+fn infinities_are_counted_outside_the_buckets() {
+    for &buckets in &[Buckets::Fixed { width: 10.0 }, Buckets::Log { base: 2.0 }] {
+        let mut histogram = Histogram::new(buckets);
+        histogram.extend(vec![f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, 4.0, 1000.0]);
+        assert_eq!(histogram.overflow(), 2);
+        assert_eq!(histogram.underflow(), 1);
+        assert_eq!(histogram.count(), 2);
+        assert!(histogram.buckets().all(|b| b.low.is_finite() && b.low < b.high), "{:?}", buckets);
+    }
+}
+
+#[test]
+fn values_on_a_boundary_go_to_the_upper_bucket() {
+    let mut histogram = Histogram::fixed(0.1);
+    histogram.extend((0..100).map(|i| i as f64 * 0.1));
+    for bucket in histogram.buckets() {
+        assert_eq!(bucket.count, 1, "{:?}", bucket);
+    }
+
+    let histogram: Histogram = (0..20).map(|i| 2f64.powi(i)).collect();
+    assert!(histogram.buckets().all(|b| b.count == 1));
+    assert_eq!(histogram.buckets().count(), 20);
+}
+
+#[test]
+fn histogram_quantile_bucket_contains_the_exact_quantile() {
+    let values = stream(20_000);
+    let exact = sorted(&values);
+    let positive: Vec<f64> = exact.iter().cloned().filter(|&x| x > 0.0).collect();
+
+    let log = {
+        let mut histogram = Histogram::new(Buckets::Log { base: 1.1 });
+        histogram.extend(&values);
+        histogram
+    };
+    let fixed = {
+        let mut histogram = Histogram::fixed(250.0);
+        histogram.extend(&values);
+        histogram
+    };
+    assert_eq!(log.underflow() as usize, exact.len() - positive.len());
+
+    for &phi in &[0.01, 0.25, 0.5, 0.9, 0.95, 0.99, 1.0] {
+        let expected = positive[((phi * positive.len() as f64).ceil() as usize).max(1) - 1];
+        let bucket = log.quantile(phi).unwrap();
+        assert!(bucket.low <= expected && expected < bucket.high, "{:?} {}", bucket, expected);
+        assert!(bucket.high / bucket.low <= 1.1 + 1e-12);
+
+        let expected = exact[((phi * exact.len() as f64).ceil() as usize).max(1) - 1];
+        let bucket = fixed.quantile(phi).unwrap();
+        assert!(bucket.low <= expected && expected < bucket.high, "{:?} {}", bucket, expected);
+    }
+}
@@ -0,0 +1,117 @@
+use std::io::Write;
+
+use rust_iterator_study::repl::{self, parse_line, Registry, ReplError, Session, Value};
+
+fn session() -> Session {
+    Session::new(Registry::standard()).limit(5)
+}
+
+#[test]
+fn parse_line_splits_stages_and_quoted_words() {
+    let stages = parse_line(r#"words "a | b" | map upper|take 2"#).unwrap();
+    assert_eq!(stages, vec![vec!["words".to_string(), "a | b".to_string()],
+                            vec!["map".to_string(), "upper".to_string()],
+                            vec!["take".to_string(), "2".to_string()]]);
+    assert_eq!(parse_line("  ").unwrap(), Vec::<Vec<String>>::new());
+    assert_eq!(parse_line(r#"words "open"#), Err(ReplError::UnterminatedQuote));
+}
+
+#[test]
+fn each_stage_is_printed() {
+    let mut s = session();
+    assert_eq!(s.eval("range 0.. | filter even | map sq | take 3").unwrap(),
+               "[1] range 0.. => 0, 1, 2, 3, 4, ...\n\
+                [2] filter even => 0, 2, 4, 6, 8, ...\n\
+                [3] map sq => 0, 4, 16, 36, 64, ...\n\
+                [4] take 3 => 0, 4, 16");
+
+    // ソース以外で始まる行は今のパイプラインに繋がる
+    assert_eq!(s.eval(r#"zip words "a b""#).unwrap(), r#"[5] zip words "a b" => (0, "a"), (4, "b")"#);
+    assert_eq!(s.stages().len(), 5);
+    assert_eq!(s.eval("pop").unwrap(), "[4] take 3 => 0, 4, 16");
+
+    // ソースで始まる行は新しいパイプラインを始める
+    s.eval("words \"Portland Fossil Greenhorn\"").unwrap();
+    assert_eq!(s.eval("enumerate").unwrap(), r#"[2] enumerate => (0, "Portland"), (1, "Fossil"), (2, "Greenhorn")"#);
+    assert_eq!(s.stages().len(), 2);
+}
+
+#[test]
+fn count_and_sum_consume_the_pipeline() {
+    let mut s = session();
+    s.eval("range 1..=100 | filter odd").unwrap();
+    assert_eq!(s.eval("count").unwrap(), "50");
+    assert_eq!(s.eval("sum").unwrap(), "2500");
+
+    // 無限のパイプラインは、ソースから取り出した数が上限に達したところでエラーになる
+    s.eval("words \"ab cde\" | map len | cycle").unwrap();
+    assert_eq!(s.eval("count"), Err(ReplError::Limit { source: "cycle".to_string(), limit: repl::MAX_PULLED }));
+
+    // 値が1つも出てこないパイプラインも、取り出した数で止まる
+    let out = s.eval("range 0.. | filter negative").unwrap();
+    assert!(out.ends_with(&format!("[2] filter negative => <error: range: stopped after {} items (try `take N`)>",
+                                   repl::MAX_PULLED)));
+    assert_eq!(s.eval("sum"), Err(ReplError::Limit { source: "range".to_string(), limit: repl::MAX_PULLED }));
+
+    // 上限を超えた後もエラーが続くので、skipやstep_byで読み捨てても失われない
+    let out = s.eval("range 0.. | skip 2000000").unwrap();
+    assert!(out.ends_with(&format!("[2] skip 2000000 => <error: range: stopped after {} items (try `take N`)>",
+                                   repl::MAX_PULLED)));
+    s.eval("range 0.. | step_by 3000000").unwrap();
+    assert_eq!(s.eval("count"), Err(ReplError::Limit { source: "range".to_string(), limit: repl::MAX_PULLED }));
+}
+
+#[test]
+fn unbounded_range_stops_at_the_largest_integer() {
+    let mut s = session();
+    assert_eq!(s.eval("range 9223372036854775807..").unwrap(), "[1] range 9223372036854775807.. => 9223372036854775807");
+    assert_eq!(s.eval("count").unwrap(), "1");
+}
+
+#[test]
+fn errors_leave_the_pipeline_unchanged() {
+    let mut s = session();
+    assert_eq!(s.eval("map sq"), Err(ReplError::NoSource));
+    s.eval("range 0..3").unwrap();
+    assert_eq!(s.eval("frobnicate").unwrap_err().to_string(), "unknown command `frobnicate` (try `help`)");
+    assert_eq!(s.eval("take many").unwrap_err().to_string(), "take: expected a count, found `many`");
+    assert_eq!(s.eval("filter").unwrap_err().to_string(), "filter: expected a predicate name");
+    assert!(s.eval("lines /no/such/file").is_err());
+    assert_eq!(s.stages().len(), 1);
+
+    // 型が合わない関数は、その値のところでエラーとして表示される
+    assert_eq!(s.eval("map upper").unwrap(), "[2] map upper => <error: upper: cannot be applied to 0>");
+    assert_eq!(s.eval("sum").unwrap_err(), ReplError::Type { function: "upper", value: Value::Int(0) });
+}
+
+#[test]
+fn registry_can_be_extended() {
+    let registry = Registry::standard()
+        .map("half", "2で割る", |v| match v {
+            Value::Int(n) => Ok(Value::Int(n / 2)),
+            other => Err(ReplError::Type { function: "half", value: other })
+        });
+    let mut s = Session::new(registry);
+    assert_eq!(s.eval("range 0..5 | map half").unwrap().lines().last(), Some("[2] map half => 0, 0, 1, 1, 2"));
+}
+
+#[test]
+fn lines_reads_a_file_lazily() {
+    let path = std::env::temp_dir().join(format!("repl-lines-{}.txt", std::process::id()));
+    let mut file = std::fs::File::create(&path).unwrap();
+    writeln!(file, "north\n\nsouth").unwrap();
+
+    let mut s = session();
+    let out = s.eval(&format!("lines \"{}\" | filter nonempty | map upper", path.display())).unwrap();
+    assert!(out.ends_with(r#"[3] map upper => "NORTH", "SOUTH""#));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn run_reads_commands_until_quit() {
+    let input = "range 0..3\nbogus\nquit\nrange 5..6\n";
+    let mut out = Vec::new();
+    repl::run(&mut session(), input.as_bytes(), &mut out, false).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(),
+               "[1] range 0..3 => 0, 1, 2\nerror: unknown command `bogus` (try `help`)\n");
+}
@@ -0,0 +1,90 @@
+use rust_iterator_study::fizzbuzz::fizz_buzz;
+use rust_iterator_study::schedule::{Periodic, Schedule, Tick};
+
+fn tick<T>(tick: u64, events: Vec<T>) -> Tick<T> {
+    Tick { tick, events }
+}
+
+#[test]
+fn steps_tick_by_tick() {
+    let schedule = Schedule::new()
+        .with(Periodic::every(2, 'a'))
+        .with(Periodic::every(3, 'b').offset(1));
+    let ticks: Vec<Tick<char>> = schedule.take(5).collect();
+    assert_eq!(ticks, vec![tick(0, vec!['a']), tick(1, vec!['b']), tick(2, vec!['a']),
+                           tick(3, vec![]), tick(4, vec!['a', 'b'])]);
+}
+
+#[test]
+fn reproduces_fizz_buzz() {
+    let schedule = Schedule::new()
+        .with(Periodic::every(3, "fizz").offset(3))
+        .with(Periodic::every(5, "buzz").offset(5))
+        .start_at(1);
+    let lines = schedule.map(|t| if t.events.is_empty() { t.tick.to_string() } else { t.events.concat() });
+    assert!(lines.take(300).eq(fizz_buzz().take(300)));
+}
+
+#[test]
+fn end_conditions() {
+    let schedule = Schedule::new()
+        .with(Periodic::every(4, "until").until(12))
+        .with(Periodic::every(5, "times").offset(2).times(2));
+    let fired: Vec<Tick<&str>> = schedule.clone().fired().collect();
+    assert_eq!(fired, vec![tick(0, vec!["until"]), tick(2, vec!["times"]), tick(4, vec!["until"]),
+                           tick(7, vec!["times"]), tick(8, vec!["until"])]);
+
+    // 全てが終わると、tickごとのイテレータも終わる
+    assert_eq!(schedule.count(), 9);
+    assert_eq!(Schedule::<u8>::new().next(), None);
+}
+
+#[test]
+fn fired_jumps_over_idle_ticks() {
+    let mut schedule = Schedule::new()
+        .with(Periodic::every(1_000_000_000_000, "rare").offset(7))
+        .with(Periodic::every(3_000_000_000_000, "rarer").offset(7));
+    assert_eq!(schedule.next_fired_at(), Some(7));
+    assert_eq!(schedule.next_fired(), Some(tick(7, vec!["rare", "rarer"])));
+    assert_eq!(schedule.tick(), Some(8));
+
+    let fired: Vec<u64> = schedule.fired().take(3).map(|t| t.tick).collect();
+    assert_eq!(fired, vec![1_000_000_000_007, 2_000_000_000_007, 3_000_000_000_007]);
+}
+
+#[test]
+fn nth_and_next_at_are_computed_directly() {
+    let mut schedule = Schedule::new().with(Periodic::every(10, 'x').offset(5));
+    assert_eq!(schedule.nth(1_000_000_000_005), Some(tick(1_000_000_000_005, vec!['x'])));
+    assert_eq!(schedule.next(), Some(tick(1_000_000_000_006, vec![])));
+
+    let periodic = Periodic::every(10, ()).offset(5).times(3);
+    let starts: Vec<Option<u64>> = [0, 5, 6, 15, 25, 26].iter().map(|&t| periodic.next_at(t)).collect();
+    assert_eq!(starts, vec![Some(5), Some(5), Some(15), Some(15), Some(25), None]);
+    assert_eq!(Periodic::every(u64::MAX, ()).next_at(1), Some(u64::MAX));
+    assert_eq!(Periodic::every(u64::MAX, ()).offset(1).next_at(2), None);
+}
+
+#[test]
+fn stops_after_the_last_tick() {
+    let schedule = Schedule::new().with(Periodic::every(1, 'x')).start_at(u64::MAX - 1);
+    let ticks: Vec<u64> = schedule.clone().map(|t| t.tick).collect();
+    assert_eq!(ticks, vec![u64::MAX - 1, u64::MAX]);
+
+    let mut fired = schedule.clone().fired();
+    assert_eq!(fired.nth(1), Some(tick(u64::MAX, vec!['x'])));
+    assert_eq!(fired.next(), None);
+
+    let mut schedule = schedule;
+    assert_eq!(schedule.clone().nth(2), None);
+    assert_eq!(schedule.nth(1), Some(tick(u64::MAX, vec!['x'])));
+    assert_eq!(schedule.tick(), None);
+    assert!(schedule.is_finished());
+    assert_eq!(schedule.next_fired(), None);
+}
+
+#[test]
+#[should_panic(expected = "period must be positive")]
+fn zero_period_is_rejected() {
+    Periodic::every(0, ());
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+use rust_iterator_study::select::SelectExt;
+
+fn shuffled(n: u64) -> Vec<u64> {
+    (0..n).map(|i| (i * 7_919 + 13) % 1_009).collect()
+}
+
+#[test]
+fn top_and_bottom_k_match_sorting() {
+    let values = shuffled(5_000);
+    let mut sorted = values.clone();
+    sorted.sort();
+
+    for &k in &[0, 1, 5, 100, 5_000, 6_000] {
+        let expected_bottom: Vec<u64> = sorted.iter().cloned().take(k).collect();
+        let expected_top: Vec<u64> = sorted.iter().rev().cloned().take(k).collect();
+        assert_eq!(values.iter().cloned().bottom_k(k), expected_bottom);
+        assert_eq!(values.iter().cloned().top_k(k), expected_top);
+    }
+}
+
+#[test]
+fn kth_smallest_matches_sorting() {
+    let values = shuffled(500);
+    let mut sorted = values.clone();
+    sorted.sort();
+
+    for (k, expected) in sorted.iter().enumerate() {
+        assert_eq!(values.iter().kth_smallest(k), Some(expected));
+    }
+    assert_eq!(values.iter().kth_smallest(500), None);
+    assert_eq!(std::iter::empty::<u8>().kth_smallest(0), None);
+    assert_eq!(values.iter().kth_smallest(usize::MAX), None);
+}
+
+#[test]
+fn five_largest_cities() {
+    let mut populations = HashMap::new();
+    populations.insert("Portland",  583_776);
+    populations.insert("Fossil",        449);
+    populations.insert("Greenhorn",       2);
+    populations.insert("Bording",     7_762);
+    populations.insert("The Dalles", 15_340);
+    populations.insert("Eugene",    156_185);
+    populations.insert("Salem",     165_265);
+
+    let names: Vec<&str> = populations.iter()
+        .top_k_by_key(5, |&(_name, pop)| *pop)
+        .into_iter()
+        .map(|(name, _pop)| *name)
+        .collect();
+    assert_eq!(names, vec!["Portland", "Salem", "Eugene", "The Dalles", "Bording"]);
+
+    let smallest: Vec<&str> = populations.iter()
+        .bottom_k_by_key(2, |&(_name, pop)| *pop)
+        .into_iter()
+        .map(|(name, _pop)| *name)
+        .collect();
+    assert_eq!(smallest, vec!["Greenhorn", "Fossil"]);
+}
+
+#[test]
+fn equal_keys_keep_their_original_order() {
+    let words = ["pear", "fig", "plum", "kiwi", "date", "lime", "apple"];
+    assert_eq!(words.iter().top_k_by_key(3, |w| w.len()), vec![&"apple", &"pear", &"plum"]);
+    assert_eq!(words.iter().bottom_k_by_key(3, |w| w.len()), vec![&"fig", &"pear", &"plum"]);
+}
+
+#[test]
+fn works_on_infinite_prefixes_and_non_clone_items() {
+    // アイテムがClone出来なくても使える
+    let strings = (0..100).map(|i| format!("{:03}", (i * 37) % 100));
+    assert_eq!(strings.top_k(2), vec!["099".to_string(), "098".to_string()]);
+    assert_eq!((1..).take(1_000_000).map(|i: u64| i * 3 % 1_000_003).top_k(1), vec![1_000_002]);
+}
+
+#[test]
+fn huge_k_returns_every_item() {
+    let values = shuffled(100);
+    let mut sorted = values.clone();
+    sorted.sort();
+    assert_eq!(values.iter().cloned().bottom_k(usize::MAX), sorted);
+    sorted.reverse();
+    assert_eq!(values.iter().cloned().top_k(usize::MAX), sorted);
+}
@@ -0,0 +1,85 @@
+use rust_iterator_study::arith::{factorial, triangle};
+use rust_iterator_study::contract;
+use rust_iterator_study::sequences::{collatz, factorials, fibonacci, pentagonal, polygonal, primes, squares, triangular};
+
+// 試し割りで確かめる
+fn is_prime(n: u64) -> bool {
+    n >= 2 && (2..).take_while(|d| d * d <= n).all(|d| !n.is_multiple_of(d))
+}
+
+#[test]
+fn primes_match_trial_division() {
+    let expected: Vec<u64> = (0..20_000).filter(|&n| is_prime(n)).collect();
+    let actual: Vec<u64> = primes().take_while(|&p| p < 20_000).collect();
+    assert_eq!(actual, expected);
+    assert_eq!(primes().nth(9_999), Some(104_729));
+}
+
+#[test]
+fn fibonacci_values_and_fast_nth() {
+    let first: Vec<u64> = fibonacci().take(12).collect();
+    assert_eq!(first, vec![0, 1, 1, 2, 3, 5, 8, 13, 21, 34, 55, 89]);
+
+    // 全て生成した場合とnth()で飛ばした場合が一致する
+    let all: Vec<u64> = fibonacci().collect();
+    assert_eq!(all.len(), 94);
+    assert_eq!(all.last(), Some(&12200160415121876738));
+    for n in 0..100 {
+        assert_eq!(fibonacci().nth(n), all.get(n).cloned());
+        let mut iter = fibonacci();
+        iter.nth(n / 2);
+        assert_eq!(iter.nth(n - n / 2), all.get(n + 1).cloned(), "{}", n);
+    }
+    assert_eq!(fibonacci().len(), 94);
+    assert_eq!(fibonacci().skip(90).collect::<Vec<_>>(), all[90..].to_vec());
+}
+
+#[test]
+fn figurate_numbers() {
+    assert_eq!(triangular().take(5).collect::<Vec<_>>(), vec![1, 3, 6, 10, 15]);
+    assert_eq!(squares().take(5).collect::<Vec<_>>(), vec![1, 4, 9, 16, 25]);
+    assert_eq!(pentagonal().take(5).collect::<Vec<_>>(), vec![1, 5, 12, 22, 35]);
+    assert_eq!(polygonal(6).take(5).collect::<Vec<_>>(), vec![1, 6, 15, 28, 45]);
+
+    // arith::triangle()と一致し、nth()は一般項から求める
+    for n in 1..200u64 {
+        assert_eq!(triangular().nth(n as usize - 1), Some(triangle(n)));
+    }
+    assert_eq!(squares().nth(4_294_967_294), Some(u32::MAX as u64 * u32::MAX as u64));
+    assert_eq!(squares().nth(4_294_967_295), None);
+    assert_eq!(triangular().nth(1_000_000), Some(500_001_500_001));
+}
+
+#[test]
+fn collatz_trajectories() {
+    assert_eq!(collatz(6).collect::<Vec<_>>(), vec![6, 3, 10, 5, 16, 8, 4, 2, 1]);
+    assert_eq!(collatz(1).collect::<Vec<_>>(), vec![1]);
+    assert_eq!(collatz(27).count(), 112);
+    assert_eq!(collatz(27).max(), Some(9_232));
+
+    // 3倍するとu64に収まらない場合はそこで終わる
+    assert_eq!(collatz(u64::MAX).collect::<Vec<_>>(), vec![u64::MAX]);
+}
+
+#[test]
+fn factorials_match_arith() {
+    let all: Vec<u64> = factorials().collect();
+    assert_eq!(all.len(), 21);
+    for (n, &f) in all.iter().enumerate() {
+        assert_eq!(f, factorial(n as u64));
+        assert_eq!(factorials().nth(n), Some(f));
+    }
+    assert_eq!(factorials().nth(21), None);
+    assert_eq!(factorials().skip(3).take(3).collect::<Vec<_>>(), vec![6, 24, 120]);
+}
+
+#[test]
+fn sequences_keep_the_iterator_contract() {
+    assert!(contract::check_iterator(fibonacci).is_ok());
+    assert!(contract::check_exact_size(fibonacci).is_ok());
+    assert!(contract::check_iterator(factorials).is_ok());
+    assert!(contract::check_exact_size(factorials).is_ok());
+    assert!(contract::check_iterator(|| collatz(97)).is_ok());
+    assert!(contract::check_iterator(|| primes().take(100)).is_ok());
+    assert!(contract::check_iterator(|| squares().take(100)).is_ok());
+}
@@ -0,0 +1,97 @@
+use std::f64::consts::{LN_2, PI};
+
+use rust_iterator_study::series::SeriesExt;
+
+// 1 - 1/3 + 1/5 - ... = π/4
+fn leibniz() -> impl Iterator<Item=f64> {
+    (0..).map(|k| if k % 2 == 0 { 4.0 } else { -4.0 } / (2 * k + 1) as f64)
+}
+
+// 1 - 1/2 + 1/3 - ... = log 2
+fn alternating_harmonic() -> impl Iterator<Item=f64> {
+    (1..).map(|k| if k % 2 == 1 { 1.0 } else { -1.0 } / k as f64)
+}
+
+// 1 + 1/4 + 1/9 + ... = π^2/6
+fn basel() -> impl Iterator<Item=f64> {
+    (1..).map(|k| 1.0 / (k as f64 * k as f64))
+}
+
+fn error<I: Iterator<Item=f64>>(iter: I, expected: f64) -> f64 {
+    (iter.last().unwrap() - expected).abs()
+}
+
+#[test]
+fn partial_sums_converge_slowly() {
+    let sums: Vec<f64> = leibniz().take(4).partial_sums().collect();
+    assert_eq!(sums, vec![4.0, 4.0 - 4.0 / 3.0, 4.0 - 4.0 / 3.0 + 0.8, 4.0 - 4.0 / 3.0 + 0.8 - 4.0 / 7.0]);
+
+    // 20項でも誤差は0.05ほど残る
+    assert!(error(leibniz().take(20).partial_sums(), PI) > 0.04);
+    assert!(error(alternating_harmonic().take(20).partial_sums(), LN_2) > 0.02);
+}
+
+#[test]
+fn aitken_accelerates_alternating_series() {
+    for &n in &[10, 14, 20] {
+        let plain = error(leibniz().take(n).partial_sums(), PI);
+        let once = error(leibniz().take(n).partial_sums().aitken(), PI);
+        let twice = error(leibniz().take(n).partial_sums().aitken().aitken(), PI);
+        assert!(once < plain / 100.0, "{}: {} {}", n, plain, once);
+        assert!(twice < once / 10.0, "{}: {} {}", n, once, twice);
+    }
+    assert!(error(leibniz().take(14).partial_sums().aitken().aitken().aitken(), PI) < 1e-7);
+    assert!(error(alternating_harmonic().take(20).partial_sums().aitken().aitken(), LN_2) < 1e-7);
+
+    // 3つの部分和から1つの値を作るので、2つ少なくなる
+    assert_eq!(leibniz().take(10).partial_sums().aitken().count(), 8);
+    assert_eq!(vec![1.0, 2.0].into_iter().aitken().next(), None);
+}
+
+#[test]
+fn euler_transform_accelerates_alternating_series() {
+    for &n in &[10, 20, 30] {
+        let plain = error(alternating_harmonic().take(n).partial_sums(), LN_2);
+        let euler = error(alternating_harmonic().take(n).euler_transform(), LN_2);
+        assert!(euler < plain / 500.0, "{}: {} {}", n, plain, euler);
+    }
+    // 項が1つ増えるごとに誤差がおよそ半分になる
+    assert!(error(alternating_harmonic().take(40).euler_transform(), LN_2) < 1e-12);
+    assert!(error(leibniz().take(40).euler_transform(), PI) < 1e-10);
+    assert_eq!(leibniz().take(7).euler_transform().count(), 7);
+}
+
+#[test]
+fn richardson_removes_the_leading_error_term() {
+    let zeta2 = PI * PI / 6.0;
+    let plain = error(basel().take(100).partial_sums(), zeta2);
+    let first = error(basel().take(100).partial_sums().richardson(1), zeta2);
+    let second = error(basel().take(100).partial_sums().richardson(1).richardson(2), zeta2);
+    assert!(plain > 0.009);
+    assert!(first < plain / 100.0, "{} {}", plain, first);
+    assert!(second < first / 100.0, "{} {}", first, second);
+    assert_eq!(basel().take(10).partial_sums().richardson(1).count(), 9);
+}
+
+#[test]
+fn compensated_summation() {
+    // 大きな値で打ち消し合う場合、単純な和とKahanの方法では1.0が失われる
+    let values = [1.0, 1e100, 1.0, -1e100];
+    assert_eq!(values.iter().sum::<f64>(), 0.0);
+    assert_eq!(values.iter().cloned().kahan_sum(), 0.0);
+    assert_eq!(values.iter().cloned().neumaier_sum(), 2.0);
+    assert_eq!(values.iter().cloned().compensated_sums().last(), Some(2.0));
+
+    // 0.1を100万回足すと、単純な和では誤差が積み重なる
+    let tenths = || std::iter::repeat_n(0.1, 1_000_000);
+    assert!((tenths().sum::<f64>() - 100_000.0).abs() > 1e-6);
+    assert_eq!(tenths().kahan_sum(), 100_000.0);
+    assert_eq!(tenths().neumaier_sum(), 100_000.0);
+    assert_eq!(tenths().compensated_sums().nth(9), Some(1.0));
+}
+
+#[test]
+#[should_panic(expected = "order must be positive")]
+fn richardson_rejects_order_zero() {
+    basel().partial_sums().richardson(0);
+}
@@ -0,0 +1,97 @@
+use rust_iterator_study::stats::Stats;
+
+// 2回走査して素直に求めた値と比べる
+fn exact(values: &[f64]) -> (f64, f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let m2: f64 = values.iter().map(|x| (x - mean).powi(2)).sum();
+    let m3: f64 = values.iter().map(|x| (x - mean).powi(3)).sum();
+    (mean, m2 / n, n.sqrt() * m3 / m2.powf(1.5))
+}
+
+fn assert_close(actual: Option<f64>, expected: f64) {
+    let actual = actual.unwrap();
+    assert!((actual - expected).abs() <= 1e-9 * expected.abs().max(1.0), "{} != {}", actual, expected);
+}
+
+fn sample() -> Vec<f64> {
+    (1..=200).map(|i| ((i * 37 % 101) as f64).powf(1.3) - 40.0).collect()
+}
+
+#[test]
+fn single_pass_matches_two_pass() {
+    let values = sample();
+    let stats: Stats = values.iter().collect();
+    let (mean, variance, skewness) = exact(&values);
+
+    assert_eq!(stats.count(), 200);
+    assert_close(stats.mean(), mean);
+    assert_close(stats.variance(), variance);
+    assert_close(stats.sample_variance(), variance * 200.0 / 199.0);
+    assert_close(stats.std_dev(), variance.sqrt());
+    assert_close(stats.skewness(), skewness);
+    assert_eq!(stats.min(), values.iter().cloned().fold(None, |m: Option<f64>, x| Some(m.map_or(x, |m| m.min(x)))));
+    assert_eq!(stats.max(), values.iter().cloned().fold(None, |m: Option<f64>, x| Some(m.map_or(x, |m| m.max(x)))));
+}
+
+#[test]
+fn large_offset_does_not_lose_precision() {
+    // 二乗和から平均の二乗を引く方法では桁落ちして0や負の値になる
+    let stats: Stats = [4.0, 7.0, 13.0, 16.0].iter().map(|x| x + 1e9).collect();
+    assert_eq!(stats.mean(), Some(1e9 + 10.0));
+    assert_eq!(stats.variance(), Some(22.5));
+    assert_eq!(stats.sample_variance(), Some(30.0));
+    assert_eq!(stats.skewness(), Some(0.0));
+}
+
+#[test]
+fn merged_chunks_match_a_single_pass() {
+    let values = sample();
+    let whole: Stats = values.iter().collect();
+
+    for &size in &[1, 7, 64, 150] {
+        let mut merged = Stats::new();
+        for chunk in values.chunks(size) {
+            merged.merge(&chunk.iter().collect());
+        }
+        assert_eq!(merged.count(), whole.count());
+        assert_close(merged.mean(), whole.mean().unwrap());
+        assert_close(merged.variance(), whole.variance().unwrap());
+        assert_close(merged.skewness(), whole.skewness().unwrap());
+        assert_eq!((merged.min(), merged.max()), (whole.min(), whole.max()));
+    }
+
+    // 空の集計結果とのmergeでは何も変わらない
+    let mut stats = whole;
+    stats.merge(&Stats::new());
+    assert_eq!(stats, whole);
+}
+
+#[test]
+fn extend_continues_the_accumulation() {
+    let mut stats: Stats = vec![1.0, 2.0].into_iter().collect();
+    stats.extend(vec![3.0, 4.0]);
+    stats.extend(&[5.0]);
+    assert_eq!(stats.count(), 5);
+    assert_eq!(stats.mean(), Some(3.0));
+    assert_eq!(stats.variance(), Some(2.0));
+}
+
+#[test]
+fn empty_and_degenerate_input() {
+    let empty: Stats = Vec::<f64>::new().into_iter().collect();
+    assert!(empty.is_empty());
+    assert_eq!(empty.count(), 0);
+    assert_eq!(empty.mean(), None);
+    assert_eq!(empty.variance(), None);
+    assert_eq!(empty.sample_variance(), None);
+    assert_eq!(empty.skewness(), None);
+    assert_eq!((empty.min(), empty.max()), (None, None));
+
+    // 値が1つなら分散は0だが、標本分散と歪度は定義できない
+    let one: Stats = std::iter::once(42.0).collect();
+    assert_eq!(one.variance(), Some(0.0));
+    assert_eq!(one.sample_variance(), None);
+    assert_eq!(one.skewness(), None);
+    assert_eq!((one.min(), one.max()), (Some(42.0), Some(42.0)));
+}